@@ -2,22 +2,906 @@
 //!
 //! Trapper (or transparent wrapper) allows for the creation of transparent type wrappers,
 //! that is types which are transparent and can be wrapped and unwrapped for zero cost.
+//!
+//! Every wrapper implements [`Transparent`], which converts pointers between the wrapper and its
+//! inner type, even if the inner type is dynamically sized like `str` or `[T]`. References are
+//! converted through the [`WrapRef`], [`WrapMut`], [`UnwrapRef`] and [`UnwrapMut`] capabilities,
+//! which a wrapper can opt out of when a direction isn't sound for it. Wrappers with a sized inner
+//! type also implement [`Wrapper`], which adds by-value conversions. All of the traits can be
+//! imported through the [`prelude`].
+//!
+//! Wrappers are created with the [`newtype!`] macro, the [`transparent`] attribute on an ordinary
+//! struct, or by deriving `Wrapper` on an existing `#[repr(transparent)]` struct.
+//!
+//! # Features
+//!
+//! * `alloc` (enabled by default) - adds conversions between heap containers like `Box`, `Vec`,
+//!   `Rc` and `Arc` of a wrapper and its inner type. Without it the crate is `no_std` and doesn't
+//!   require an allocator.
 
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 extern crate self as trapper;
+#[cfg(test)]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, boxed::Box, rc::Rc, sync::Arc, vec::Vec};
+use core::mem::ManuallyDrop;
+use core::ptr::{self, NonNull};
+
+/// A transparent type wrapper. This trait provides methods for converting between pointers to a
+/// wrapper and its inner type, which may be dynamically sized. It should only be implemented by
+/// types through the `newtype` macro. If it must be implemented manually, the type should have
+/// transparent representation to be safe.
+///
+/// Converting references is split into the [`WrapRef`], [`WrapMut`], [`UnwrapRef`] and
+/// [`UnwrapMut`] capabilities, since not every direction is sound for every wrapper.
+///
+/// # Safety
+///
+/// Implementors must have the same layout and ABI as `Inner`, which `#[repr(transparent)]`
+/// guarantees for a struct with a single field of type `Inner`. `wrap_ptr` and `unwrap_ptr` must
+/// only cast the pointer they're given, and every provided method of this trait and the
+/// capability traits dereferences their results under this assumption. Manual implementations
+/// can check the layout with [`assert_transparent!`].
+pub unsafe trait Transparent {
+    /// The inner wrapped type
+    type Inner: ?Sized;
+
+    /// Casts a raw pointer to the value into a raw pointer to the wrapper type.
+    ///
+    /// This is safe since the pointer is never dereferenced. Because the wrapper has transparent
+    /// representation, the resulting pointer can be passed across an FFI boundary anywhere a
+    /// pointer to the inner type is expected, and can be dereferenced wherever the original
+    /// pointer could be.
+    ///
+    /// Implementations must return the same address with the same metadata, which for a
+    /// transparent struct is `inner as *const Self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type Handle(u32));
+    ///
+    /// # fn main() {
+    /// let raw: u32 = 7;
+    /// let handle: *const Handle = Handle::wrap_ptr(&raw);
+    ///
+    /// assert_eq!(unsafe { *(*handle).unwrap_ref() }, 7);
+    /// # }
+    /// ```
+    fn wrap_ptr(inner: *const Self::Inner) -> *const Self;
+    /// Casts a raw pointer to the wrapper into a raw pointer to the underlying type.
+    ///
+    /// This is safe since the pointer is never dereferenced. Because the wrapper has transparent
+    /// representation, the resulting pointer can be handed to foreign code that expects a
+    /// pointer to the inner type.
+    ///
+    /// Implementations must return the same address with the same metadata, which for a
+    /// transparent struct is `wrapper as *const Self::Inner`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type Handle(u32));
+    ///
+    /// # fn main() {
+    /// let handle = Handle::wrap(7);
+    /// let raw: *const u32 = Handle::unwrap_ptr(&handle);
+    ///
+    /// assert_eq!(unsafe { *raw }, 7);
+    /// # }
+    /// ```
+    fn unwrap_ptr(wrapper: *const Self) -> *const Self::Inner;
+
+    /// Casts a mutable raw pointer to the value into a mutable raw pointer to the wrapper type.
+    ///
+    /// This is safe for the same reasons as [`wrap_ptr`](Transparent::wrap_ptr).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type Handle(u32));
+    ///
+    /// # fn main() {
+    /// let mut raw: u32 = 7;
+    /// let handle: *mut Handle = Handle::wrap_ptr_mut(&mut raw);
+    /// unsafe { *handle = Handle::wrap(8) };
+    ///
+    /// assert_eq!(raw, 8);
+    /// # }
+    /// ```
+    fn wrap_ptr_mut(inner: *mut Self::Inner) -> *mut Self {
+        Self::wrap_ptr(inner) as *mut Self
+    }
+    /// Casts a non-null pointer to the value into a non-null pointer to the wrapper type.
+    ///
+    /// This is safe for the same reasons as [`wrap_ptr`](Transparent::wrap_ptr).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::ptr::NonNull;
+    /// use trapper::prelude::*;
+    /// newtype!(type Handle(u32));
+    ///
+    /// # fn main() {
+    /// let mut raw: u32 = 7;
+    /// let handle: NonNull<Handle> = Handle::wrap_non_null(NonNull::from(&mut raw));
+    ///
+    /// assert_eq!(unsafe { *handle.as_ref().unwrap_ref() }, 7);
+    /// # }
+    /// ```
+    fn wrap_non_null(inner: NonNull<Self::Inner>) -> NonNull<Self> {
+        unsafe { NonNull::new_unchecked(Self::wrap_ptr_mut(inner.as_ptr())) }
+    }
+    /// Casts a mutable raw pointer to the wrapper into a mutable raw pointer to the underlying
+    /// type.
+    ///
+    /// This is safe for the same reasons as [`unwrap_ptr`](Transparent::unwrap_ptr).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type Handle(u32));
+    ///
+    /// # fn main() {
+    /// let mut handle = Handle::wrap(7);
+    /// let raw: *mut u32 = Handle::unwrap_ptr_mut(&mut handle);
+    /// unsafe { *raw = 8 };
+    ///
+    /// assert_eq!(handle, Handle::wrap(8));
+    /// # }
+    /// ```
+    fn unwrap_ptr_mut(wrapper: *mut Self) -> *mut Self::Inner {
+        Self::unwrap_ptr(wrapper) as *mut Self::Inner
+    }
+    /// Casts a non-null pointer to the wrapper into a non-null pointer to the underlying type.
+    ///
+    /// This is safe for the same reasons as [`unwrap_ptr`](Transparent::unwrap_ptr).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::ptr::NonNull;
+    /// use trapper::prelude::*;
+    /// newtype!(type Handle(u32));
+    ///
+    /// # fn main() {
+    /// let mut handle = Handle::wrap(7);
+    /// let raw: NonNull<u32> = Handle::unwrap_non_null(NonNull::from(&mut handle));
+    ///
+    /// assert_eq!(unsafe { *raw.as_ref() }, 7);
+    /// # }
+    /// ```
+    fn unwrap_non_null(wrapper: NonNull<Self>) -> NonNull<Self::Inner> {
+        unsafe { NonNull::new_unchecked(Self::unwrap_ptr_mut(wrapper.as_ptr())) }
+    }
+}
+
+/// The capability to view shared references to the inner type as shared references to the
+/// wrapper.
+///
+/// # Safety
+///
+/// Implementors assert that every shared reference to `Inner` is also a valid shared reference to
+/// `Self`. This doesn't hold if the wrapper only accepts some values of `Inner`, or if it mutates
+/// the inner value through shared references, like a `Cell` over a type without interior
+/// mutability.
+pub unsafe trait WrapRef: Transparent {
+    /// Wraps a shared reference to the value in the wrapper type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let number = 12;
+    /// let wrapper: &NumberWrapper = NumberWrapper::wrap_ref(&number);
+    /// # }
+    /// ```
+    ///
+    /// The inner type may be dynamically sized
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(pub type Username(str));
+    ///
+    /// # fn main() {
+    /// let name: &Username = Username::wrap_ref("sydney");
+    /// assert_eq!(name.unwrap_ref(), "sydney");
+    /// # }
+    /// ```
+    fn wrap_ref(inner: &Self::Inner) -> &Self {
+        unsafe { &*Self::wrap_ptr(inner) }
+    }
+
+    /// Wraps an optional shared reference to the value in the wrapper type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let numbers = [12, 13];
+    /// let first: Option<&NumberWrapper> = NumberWrapper::wrap_option_ref(numbers.first());
+    ///
+    /// assert_eq!(first, Some(&NumberWrapper::wrap(12)));
+    /// assert_eq!(NumberWrapper::wrap_option_ref(None), None);
+    /// # }
+    /// ```
+    fn wrap_option_ref(inner: Option<&Self::Inner>) -> Option<&Self> {
+        inner.map(Self::wrap_ref)
+    }
+    /// Wraps a shared reference to the value in the wrapper type if the result is `Ok`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let number = 12;
+    /// let result: Result<&i32, ()> = Ok(&number);
+    ///
+    /// assert_eq!(NumberWrapper::wrap_result_ref(result), Ok(&NumberWrapper::wrap(12)));
+    /// assert_eq!(NumberWrapper::wrap_result_ref(Err::<&i32, _>("error")), Err("error"));
+    /// # }
+    /// ```
+    fn wrap_result_ref<E>(inner: Result<&Self::Inner, E>) -> Result<&Self, E> {
+        inner.map(Self::wrap_ref)
+    }
+
+    /// Wraps a shared slice of values in the wrapper type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    /// newtype!(#[derive(PartialEq, Debug)] type UnitWrapper(()));
+    ///
+    /// # fn main() {
+    /// let numbers = [12, 13];
+    /// let wrappers: &[NumberWrapper] = NumberWrapper::wrap_slice(&numbers);
+    /// assert_eq!(wrappers, &[NumberWrapper::wrap(12), NumberWrapper::wrap(13)]);
+    ///
+    /// let empty: &[NumberWrapper] = NumberWrapper::wrap_slice(&[]);
+    /// assert!(empty.is_empty());
+    ///
+    /// let units: &[UnitWrapper] = UnitWrapper::wrap_slice(&[(), (), ()]);
+    /// assert_eq!(units.len(), 3);
+    /// # }
+    /// ```
+    fn wrap_slice(inner: &[Self::Inner]) -> &[Self]
+    where
+        Self: Sized,
+        Self::Inner: Sized,
+    {
+        unsafe { &*(inner as *const [Self::Inner] as *const [Self]) }
+    }
+    /// Wraps a shared reference to an array of values in the wrapper type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let numbers = [12, 13];
+    /// let wrappers: &[NumberWrapper; 2] = NumberWrapper::wrap_array_ref(&numbers);
+    /// # }
+    /// ```
+    fn wrap_array_ref<const N: usize>(inner: &[Self::Inner; N]) -> &[Self; N]
+    where
+        Self: Sized,
+        Self::Inner: Sized,
+    {
+        unsafe { &*(inner as *const [Self::Inner; N] as *const [Self; N]) }
+    }
+
+    /// Wraps a reference counted value in the wrapper type, reusing the allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let number = Rc::new(12);
+    /// let wrapper: Rc<NumberWrapper> = NumberWrapper::wrap_rc(number.clone());
+    ///
+    /// assert_eq!(*wrapper, NumberWrapper::wrap(12));
+    /// assert_eq!(Rc::strong_count(&number), 2);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn wrap_rc(inner: Rc<Self::Inner>) -> Rc<Self>
+    where
+        Self: WrapMut,
+    {
+        unsafe { Rc::from_raw(Self::wrap_ptr(Rc::into_raw(inner))) }
+    }
+    /// Wraps a reference counted slice of values in the wrapper type, reusing the allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let numbers: Rc<[i32]> = Rc::from(vec![12, 13]);
+    /// let wrappers: Rc<[NumberWrapper]> = NumberWrapper::wrap_rc_slice(numbers);
+    /// assert_eq!(&*wrappers, &[NumberWrapper::wrap(12), NumberWrapper::wrap(13)]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn wrap_rc_slice(inner: Rc<[Self::Inner]>) -> Rc<[Self]>
+    where
+        Self: Sized + WrapMut,
+        Self::Inner: Sized,
+    {
+        unsafe { Rc::from_raw(Rc::into_raw(inner) as *const [Self]) }
+    }
+    /// Wraps an atomically reference counted value in the wrapper type, reusing the allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let wrapper: Arc<NumberWrapper> = NumberWrapper::wrap_arc(Arc::new(12));
+    /// assert_eq!(*wrapper, NumberWrapper::wrap(12));
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn wrap_arc(inner: Arc<Self::Inner>) -> Arc<Self>
+    where
+        Self: WrapMut,
+    {
+        unsafe { Arc::from_raw(Self::wrap_ptr(Arc::into_raw(inner))) }
+    }
+    /// Wraps an atomically reference counted slice of values in the wrapper type, reusing the
+    /// allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let numbers: Arc<[i32]> = Arc::from(vec![12, 13]);
+    /// let wrappers: Arc<[NumberWrapper]> = NumberWrapper::wrap_arc_slice(numbers);
+    /// assert_eq!(&*wrappers, &[NumberWrapper::wrap(12), NumberWrapper::wrap(13)]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn wrap_arc_slice(inner: Arc<[Self::Inner]>) -> Arc<[Self]>
+    where
+        Self: Sized + WrapMut,
+        Self::Inner: Sized,
+    {
+        unsafe { Arc::from_raw(Arc::into_raw(inner) as *const [Self]) }
+    }
+}
+
+/// The capability to view unique references to the inner type as unique references to the
+/// wrapper.
+///
+/// # Safety
+///
+/// Implementors assert that every unique reference to `Inner` is also a valid unique reference to
+/// `Self`. This doesn't hold if the wrapper only accepts some values of `Inner`.
+pub unsafe trait WrapMut: Transparent {
+    /// Wraps a unique reference to the value in the wrapper type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let mut number = 12;
+    /// let wrapper: &mut NumberWrapper = NumberWrapper::wrap_mut(&mut number);
+    /// *wrapper = NumberWrapper::wrap(13);
+    ///
+    /// assert_eq!(number, 13);
+    /// # }
+    /// ```
+    fn wrap_mut(inner: &mut Self::Inner) -> &mut Self {
+        unsafe { &mut *Self::wrap_ptr_mut(inner) }
+    }
+
+    /// Wraps an optional unique reference to the value in the wrapper type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let mut numbers = [12, 13];
+    /// if let Some(last) = NumberWrapper::wrap_option_mut(numbers.last_mut()) {
+    ///     *last = NumberWrapper::wrap(14);
+    /// }
+    ///
+    /// assert_eq!(numbers, [12, 14]);
+    /// # }
+    /// ```
+    fn wrap_option_mut(inner: Option<&mut Self::Inner>) -> Option<&mut Self> {
+        inner.map(Self::wrap_mut)
+    }
+    /// Wraps a unique reference to the value in the wrapper type if the result is `Ok`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let mut number = 12;
+    /// let result: Result<&mut i32, ()> = Ok(&mut number);
+    /// if let Ok(wrapper) = NumberWrapper::wrap_result_mut(result) {
+    ///     *wrapper = NumberWrapper::wrap(13);
+    /// }
+    ///
+    /// assert_eq!(number, 13);
+    /// # }
+    /// ```
+    fn wrap_result_mut<E>(inner: Result<&mut Self::Inner, E>) -> Result<&mut Self, E> {
+        inner.map(Self::wrap_mut)
+    }
+
+    /// Wraps a unique slice of values in the wrapper type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let mut numbers = [12, 13];
+    /// let wrappers: &mut [NumberWrapper] = NumberWrapper::wrap_slice_mut(&mut numbers);
+    /// wrappers[1] = NumberWrapper::wrap(14);
+    ///
+    /// assert_eq!(numbers, [12, 14]);
+    /// # }
+    /// ```
+    fn wrap_slice_mut(inner: &mut [Self::Inner]) -> &mut [Self]
+    where
+        Self: Sized,
+        Self::Inner: Sized,
+    {
+        unsafe { &mut *(inner as *mut [Self::Inner] as *mut [Self]) }
+    }
+    /// Wraps a unique reference to an array of values in the wrapper type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let mut numbers = [12, 13];
+    /// let wrappers: &mut [NumberWrapper; 2] = NumberWrapper::wrap_array_mut(&mut numbers);
+    /// wrappers[0] = NumberWrapper::wrap(11);
+    ///
+    /// assert_eq!(numbers, [11, 13]);
+    /// # }
+    /// ```
+    fn wrap_array_mut<const N: usize>(inner: &mut [Self::Inner; N]) -> &mut [Self; N]
+    where
+        Self: Sized,
+        Self::Inner: Sized,
+    {
+        unsafe { &mut *(inner as *mut [Self::Inner; N] as *mut [Self; N]) }
+    }
+
+    /// Wraps a boxed value in the wrapper type, reusing the allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let wrapper: Box<NumberWrapper> = NumberWrapper::wrap_box(Box::new(12));
+    /// assert_eq!(*wrapper, NumberWrapper::wrap(12));
+    ///
+    /// newtype!(pub type Username(str));
+    ///
+    /// let name: Box<Username> = Username::wrap_box(Box::from("sydney"));
+    /// assert_eq!(name.unwrap_ref(), "sydney");
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn wrap_box(inner: Box<Self::Inner>) -> Box<Self> {
+        unsafe { Box::from_raw(Self::wrap_ptr_mut(Box::into_raw(inner))) }
+    }
+}
+
+/// The capability to view shared references to the wrapper as shared references to the inner
+/// type.
+///
+/// # Safety
+///
+/// Implementors assert that a shared reference to `Self` can be used as a shared reference to
+/// `Inner`. This doesn't hold if the wrapper mutates the inner value through shared references
+/// while `Inner` assumes it can't change.
+pub unsafe trait UnwrapRef: Transparent {
+    /// Unwraps a shared reference to the wrapper, exposing the underlying type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let wrapper = NumberWrapper::wrap(12);
+    ///
+    /// assert_eq!(*wrapper.unwrap_ref(), 12);
+    /// # }
+    /// ```
+    fn unwrap_ref(&self) -> &Self::Inner {
+        unsafe { &*Self::unwrap_ptr(self) }
+    }
+
+    /// Unwraps an optional shared reference to the wrapper, exposing the underlying type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let wrappers = [NumberWrapper::wrap(12)];
+    /// assert_eq!(NumberWrapper::unwrap_option_ref(wrappers.first()), Some(&12));
+    /// # }
+    /// ```
+    fn unwrap_option_ref(wrapper: Option<&Self>) -> Option<&Self::Inner> {
+        wrapper.map(Self::unwrap_ref)
+    }
+    /// Unwraps a shared reference to the wrapper if the result is `Ok`, exposing the underlying
+    /// type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let wrapper = NumberWrapper::wrap(12);
+    /// let result: Result<&NumberWrapper, ()> = Ok(&wrapper);
+    ///
+    /// assert_eq!(NumberWrapper::unwrap_result_ref(result), Ok(&12));
+    /// # }
+    /// ```
+    fn unwrap_result_ref<E>(wrapper: Result<&Self, E>) -> Result<&Self::Inner, E> {
+        wrapper.map(Self::unwrap_ref)
+    }
+
+    /// Unwraps a shared slice of wrappers, exposing the underlying values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    /// newtype!(type UnitWrapper(()));
+    ///
+    /// # fn main() {
+    /// let wrappers = [NumberWrapper::wrap(12), NumberWrapper::wrap(13)];
+    /// assert_eq!(NumberWrapper::unwrap_slice(&wrappers), &[12, 13]);
+    ///
+    /// let empty: [NumberWrapper; 0] = [];
+    /// assert!(NumberWrapper::unwrap_slice(&empty).is_empty());
+    ///
+    /// let units = [UnitWrapper::wrap(()), UnitWrapper::wrap(())];
+    /// assert_eq!(UnitWrapper::unwrap_slice(&units), &[(), ()]);
+    /// # }
+    /// ```
+    fn unwrap_slice(wrappers: &[Self]) -> &[Self::Inner]
+    where
+        Self: Sized,
+        Self::Inner: Sized,
+    {
+        unsafe { &*(wrappers as *const [Self] as *const [Self::Inner]) }
+    }
+    /// Unwraps a shared reference to an array of wrappers, exposing the underlying values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let wrappers = [NumberWrapper::wrap(12), NumberWrapper::wrap(13)];
+    /// assert_eq!(NumberWrapper::unwrap_array_ref(&wrappers), &[12, 13]);
+    /// # }
+    /// ```
+    fn unwrap_array_ref<const N: usize>(wrappers: &[Self; N]) -> &[Self::Inner; N]
+    where
+        Self: Sized,
+        Self::Inner: Sized,
+    {
+        unsafe { &*(wrappers as *const [Self; N] as *const [Self::Inner; N]) }
+    }
+
+    /// Unwraps a reference counted wrapper, returning the reference counted inner value and
+    /// reusing the allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let number: Rc<i32> = NumberWrapper::unwrap_rc(Rc::new(NumberWrapper::wrap(12)));
+    /// assert_eq!(*number, 12);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn unwrap_rc(wrapper: Rc<Self>) -> Rc<Self::Inner>
+    where
+        Self: UnwrapMut,
+    {
+        unsafe { Rc::from_raw(Self::unwrap_ptr(Rc::into_raw(wrapper))) }
+    }
+    /// Unwraps a reference counted slice of wrappers, returning the reference counted inner
+    /// values and reusing the allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let wrappers: Rc<[NumberWrapper]> = Rc::from(vec![NumberWrapper::wrap(12)]);
+    /// assert_eq!(&*NumberWrapper::unwrap_rc_slice(wrappers), &[12]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn unwrap_rc_slice(wrappers: Rc<[Self]>) -> Rc<[Self::Inner]>
+    where
+        Self: Sized + UnwrapMut,
+        Self::Inner: Sized,
+    {
+        unsafe { Rc::from_raw(Rc::into_raw(wrappers) as *const [Self::Inner]) }
+    }
+    /// Unwraps an atomically reference counted wrapper, returning the reference counted inner
+    /// value and reusing the allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let number: Arc<i32> = NumberWrapper::unwrap_arc(Arc::new(NumberWrapper::wrap(12)));
+    /// assert_eq!(*number, 12);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn unwrap_arc(wrapper: Arc<Self>) -> Arc<Self::Inner>
+    where
+        Self: UnwrapMut,
+    {
+        unsafe { Arc::from_raw(Self::unwrap_ptr(Arc::into_raw(wrapper))) }
+    }
+    /// Unwraps an atomically reference counted slice of wrappers, returning the reference
+    /// counted inner values and reusing the allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let wrappers: Arc<[NumberWrapper]> = Arc::from(vec![NumberWrapper::wrap(12)]);
+    /// assert_eq!(&*NumberWrapper::unwrap_arc_slice(wrappers), &[12]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn unwrap_arc_slice(wrappers: Arc<[Self]>) -> Arc<[Self::Inner]>
+    where
+        Self: Sized + UnwrapMut,
+        Self::Inner: Sized,
+    {
+        unsafe { Arc::from_raw(Arc::into_raw(wrappers) as *const [Self::Inner]) }
+    }
+}
+
+/// The capability to view unique references to the wrapper as unique references to the inner
+/// type.
+///
+/// # Safety
+///
+/// Implementors assert that any value written to `Inner` through a unique reference is a valid
+/// value of `Self`. This doesn't hold if the wrapper only accepts some values of `Inner`.
+pub unsafe trait UnwrapMut: Transparent {
+    /// Unwraps a unique reference to the wrapper, exposing the underlying type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let mut wrapper = NumberWrapper::wrap(12);
+    /// *wrapper.unwrap_mut() = 13;
+    ///
+    /// assert_eq!(wrapper, NumberWrapper::wrap(13));
+    /// # }
+    /// ```
+    fn unwrap_mut(&mut self) -> &mut Self::Inner {
+        unsafe { &mut *Self::unwrap_ptr_mut(self) }
+    }
+
+    /// Unwraps an optional unique reference to the wrapper, exposing the underlying type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let mut wrappers = [NumberWrapper::wrap(12)];
+    /// if let Some(first) = NumberWrapper::unwrap_option_mut(wrappers.first_mut()) {
+    ///     *first = 13;
+    /// }
+    ///
+    /// assert_eq!(wrappers, [NumberWrapper::wrap(13)]);
+    /// # }
+    /// ```
+    fn unwrap_option_mut(wrapper: Option<&mut Self>) -> Option<&mut Self::Inner> {
+        wrapper.map(Self::unwrap_mut)
+    }
+    /// Unwraps a unique reference to the wrapper if the result is `Ok`, exposing the underlying
+    /// type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let mut wrapper = NumberWrapper::wrap(12);
+    /// let result: Result<&mut NumberWrapper, ()> = Ok(&mut wrapper);
+    /// if let Ok(number) = NumberWrapper::unwrap_result_mut(result) {
+    ///     *number = 13;
+    /// }
+    ///
+    /// assert_eq!(wrapper, NumberWrapper::wrap(13));
+    /// # }
+    /// ```
+    fn unwrap_result_mut<E>(wrapper: Result<&mut Self, E>) -> Result<&mut Self::Inner, E> {
+        wrapper.map(Self::unwrap_mut)
+    }
+
+    /// Unwraps a unique slice of wrappers, exposing the underlying values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let mut wrappers = [NumberWrapper::wrap(12), NumberWrapper::wrap(13)];
+    /// NumberWrapper::unwrap_slice_mut(&mut wrappers)[0] = 11;
+    ///
+    /// assert_eq!(wrappers, [NumberWrapper::wrap(11), NumberWrapper::wrap(13)]);
+    /// # }
+    /// ```
+    fn unwrap_slice_mut(wrappers: &mut [Self]) -> &mut [Self::Inner]
+    where
+        Self: Sized,
+        Self::Inner: Sized,
+    {
+        unsafe { &mut *(wrappers as *mut [Self] as *mut [Self::Inner]) }
+    }
+    /// Unwraps a unique reference to an array of wrappers, exposing the underlying values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let mut wrappers = [NumberWrapper::wrap(12), NumberWrapper::wrap(13)];
+    /// NumberWrapper::unwrap_array_mut(&mut wrappers)[1] = 14;
+    ///
+    /// assert_eq!(wrappers, [NumberWrapper::wrap(12), NumberWrapper::wrap(14)]);
+    /// # }
+    /// ```
+    fn unwrap_array_mut<const N: usize>(wrappers: &mut [Self; N]) -> &mut [Self::Inner; N]
+    where
+        Self: Sized,
+        Self::Inner: Sized,
+    {
+        unsafe { &mut *(wrappers as *mut [Self; N] as *mut [Self::Inner; N]) }
+    }
 
-/// A type wrapper. This trait provides methods for converting between a wrapper and its
-/// inner type. It should only be implemented by types through the `newtype` macro. If it must
-/// be implemented manually, the type should have transparent representation to be safe.
-pub unsafe trait Wrapper: Sized {
-    /// The inner wrapped type
-    type Inner: Sized;
+    /// Unwraps a boxed wrapper, returning the boxed inner value and reusing the allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let number: Box<i32> = NumberWrapper::unwrap_box(Box::new(NumberWrapper::wrap(12)));
+    /// assert_eq!(*number, 12);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn unwrap_box(wrapper: Box<Self>) -> Box<Self::Inner> {
+        unsafe { Box::from_raw(Self::unwrap_ptr_mut(Box::into_raw(wrapper))) }
+    }
+}
 
+/// A sized type wrapper. This trait provides methods for converting between a wrapper and its
+/// inner type by value, along with collections of wrappers and their inner values. It should only
+/// be implemented by types through the `newtype` macro, which implements it for every wrapper
+/// with a sized inner type.
+///
+/// # Safety
+///
+/// Implementors must uphold the same requirements as [`Transparent`], and every value of `Inner`
+/// must be a valid value of `Self`. Every provided method casts between collections of `Self`
+/// and `Inner` under this assumption.
+pub unsafe trait Wrapper: Sized + Transparent<Inner: Sized> {
     /// Wraps the value, returning a new instance of the wrapper.
     ///
     /// # Example
     ///
     /// ```
-    /// use trapper::{Wrapper, newtype};
+    /// use trapper::prelude::*;
     /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
     ///
     /// # fn main() {
@@ -32,7 +916,7 @@ pub unsafe trait Wrapper: Sized {
     /// # Example
     ///
     /// ```
-    /// use trapper::{Wrapper, newtype};
+    /// use trapper::prelude::*;
     /// newtype!(type NumberWrapper(i32));
     ///
     /// # fn main() {
@@ -42,87 +926,426 @@ pub unsafe trait Wrapper: Sized {
     /// ```
     fn unwrap(self) -> Self::Inner;
 
-    /// Wraps a shared reference to the value in the wrapper type
+    /// Wraps an array of values, returning an array of wrappers without copying each element
     ///
     /// # Example
     ///
     /// ```
-    /// use trapper::{Wrapper, newtype};
-    /// newtype!(type NumberWrapper(i32));
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    /// newtype!(#[derive(PartialEq, Debug)] type UnitWrapper(()));
     ///
     /// # fn main() {
-    /// let number = 12;
-    /// let wrapper: &NumberWrapper = NumberWrapper::wrap_ref(&number);
+    /// let wrappers = NumberWrapper::wrap_array([12, 13]);
+    /// assert_eq!(wrappers, [NumberWrapper::wrap(12), NumberWrapper::wrap(13)]);
+    ///
+    /// let empty: [NumberWrapper; 0] = NumberWrapper::wrap_array([]);
+    /// assert_eq!(empty, []);
+    ///
+    /// let units = UnitWrapper::wrap_array([(), ()]);
+    /// assert_eq!(units, [UnitWrapper::wrap(()), UnitWrapper::wrap(())]);
     /// # }
     /// ```
-    fn wrap_ref(inner: &Self::Inner) -> &Self {
-        unsafe { &*(inner as *const Self::Inner as *const Self) }
+    fn wrap_array<const N: usize>(inner: [Self::Inner; N]) -> [Self; N] {
+        let inner = ManuallyDrop::new(inner);
+        unsafe { ptr::read(&*inner as *const [Self::Inner; N] as *const [Self; N]) }
     }
-    /// Wraps a unique reference to the value in the wrapper type
+    /// Unwraps an array of wrappers, returning an array of their inner values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type Name(String));
+    ///
+    /// # fn main() {
+    /// let names = [Name::wrap("a".to_string()), Name::wrap("b".to_string())];
+    /// assert_eq!(Name::unwrap_array(names), ["a", "b"]);
+    /// # }
+    /// ```
+    fn unwrap_array<const N: usize>(wrappers: [Self; N]) -> [Self::Inner; N] {
+        let wrappers = ManuallyDrop::new(wrappers);
+        unsafe { ptr::read(&*wrappers as *const [Self; N] as *const [Self::Inner; N]) }
+    }
+
+    /// Wraps a boxed slice of values in the wrapper type, reusing the allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let numbers: Box<[i32]> = vec![12, 13].into_boxed_slice();
+    /// let wrappers: Box<[NumberWrapper]> = NumberWrapper::wrap_boxed_slice(numbers);
+    /// assert_eq!(&*wrappers, &[NumberWrapper::wrap(12), NumberWrapper::wrap(13)]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn wrap_boxed_slice(inner: Box<[Self::Inner]>) -> Box<[Self]> {
+        unsafe { Box::from_raw(Box::into_raw(inner) as *mut [Self]) }
+    }
+    /// Unwraps a boxed slice of wrappers, returning the boxed inner values and reusing the
+    /// allocation
     ///
     /// # Example
     ///
     /// ```
-    /// use trapper::{Wrapper, newtype};
+    /// use trapper::prelude::*;
     /// newtype!(type NumberWrapper(i32));
     ///
     /// # fn main() {
-    /// let mut number = 12;
-    /// let wrapper: &mut NumberWrapper = NumberWrapper::wrap_mut(&mut number);
-    /// *wrapper = NumberWrapper::wrap(13);
+    /// let wrappers = vec![NumberWrapper::wrap(12), NumberWrapper::wrap(13)].into_boxed_slice();
+    /// assert_eq!(&*NumberWrapper::unwrap_boxed_slice(wrappers), &[12, 13]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn unwrap_boxed_slice(wrappers: Box<[Self]>) -> Box<[Self::Inner]> {
+        unsafe { Box::from_raw(Box::into_raw(wrappers) as *mut [Self::Inner]) }
+    }
+    /// Wraps a vector of values in the wrapper type, reusing the allocation
     ///
-    /// assert_eq!(number, 13);
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(PartialEq, Debug)] type UserId(u64));
+    ///
+    /// # fn main() {
+    /// let ids: Vec<u64> = Vec::with_capacity(16);
+    /// let mut users: Vec<UserId> = UserId::wrap_vec(ids);
+    /// users.push(UserId::wrap(12));
+    ///
+    /// assert_eq!(users, [UserId::wrap(12)]);
+    /// assert!(users.capacity() >= 16);
     /// # }
     /// ```
-    fn wrap_mut(inner: &mut Self::Inner) -> &mut Self {
-        unsafe { &mut *(inner as *mut Self::Inner as *mut Self) }
+    #[cfg(feature = "alloc")]
+    fn wrap_vec(inner: Vec<Self::Inner>) -> Vec<Self> {
+        let mut inner = ManuallyDrop::new(inner);
+        unsafe {
+            Vec::from_raw_parts(
+                inner.as_mut_ptr() as *mut Self,
+                inner.len(),
+                inner.capacity(),
+            )
+        }
+    }
+    /// Unwraps a vector of wrappers, returning a vector of the inner values and reusing the
+    /// allocation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use trapper::prelude::*;
+    /// newtype!(type UserId(u64));
+    ///
+    /// # fn main() {
+    /// let users = vec![UserId::wrap(12), UserId::wrap(13)];
+    /// assert_eq!(UserId::unwrap_vec(users), [12, 13]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn unwrap_vec(wrappers: Vec<Self>) -> Vec<Self::Inner> {
+        let mut wrappers = ManuallyDrop::new(wrappers);
+        unsafe {
+            Vec::from_raw_parts(
+                wrappers.as_mut_ptr() as *mut Self::Inner,
+                wrappers.len(),
+                wrappers.capacity(),
+            )
+        }
     }
 
-    /// Unwraps a shared reference to the wrapper, exposing the underlying type
+    /// Wraps a copy-on-write value in the wrapper type without cloning it
     ///
     /// # Example
     ///
     /// ```
-    /// use trapper::{Wrapper, newtype};
-    /// newtype!(type NumberWrapper(i32));
+    /// use std::borrow::Cow;
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(Clone, PartialEq, Debug)] type NumberWrapper(i32));
     ///
     /// # fn main() {
-    /// let wrapper = NumberWrapper::wrap(12);
+    /// let number = 12;
+    /// let wrapper: Cow<NumberWrapper> = NumberWrapper::wrap_cow(Cow::Borrowed(&number));
     ///
-    /// assert_eq!(*wrapper.unwrap_ref(), 12);
+    /// assert!(matches!(wrapper, Cow::Borrowed(_)));
+    /// assert_eq!(*wrapper, NumberWrapper::wrap(12));
     /// # }
     /// ```
-    fn unwrap_ref(&self) -> &Self::Inner {
-        unsafe { &*(self as *const Self as *const Self::Inner) }
+    #[cfg(feature = "alloc")]
+    fn wrap_cow(inner: Cow<'_, Self::Inner>) -> Cow<'_, Self>
+    where
+        Self: Clone + WrapRef,
+        Self::Inner: Clone,
+    {
+        match inner {
+            Cow::Borrowed(inner) => Cow::Borrowed(Self::wrap_ref(inner)),
+            Cow::Owned(inner) => Cow::Owned(Self::wrap(inner)),
+        }
     }
-    /// Unwraps a unique reference to the wrapper, exposing the underlying type
+    /// Unwraps a copy-on-write wrapper, exposing the underlying type without cloning it
     ///
     /// # Example
     ///
     /// ```
-    /// use trapper::{Wrapper, newtype};
-    /// newtype!(#[derive(PartialEq, Debug)] type NumberWrapper(i32));
+    /// use std::borrow::Cow;
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(Clone)] type NumberWrapper(i32));
     ///
     /// # fn main() {
-    /// let mut wrapper = NumberWrapper::wrap(12);
-    /// *wrapper.unwrap_mut() = 13;
+    /// let wrapper: Cow<NumberWrapper> = Cow::Owned(NumberWrapper::wrap(12));
+    /// let number: Cow<i32> = NumberWrapper::unwrap_cow(wrapper);
     ///
-    /// assert_eq!(wrapper, NumberWrapper::wrap(13));
+    /// assert!(matches!(number, Cow::Owned(12)));
     /// # }
     /// ```
-    fn unwrap_mut(&mut self) -> &mut Self::Inner {
-        unsafe { &mut *(self as *mut Self as *mut Self::Inner) }
+    #[cfg(feature = "alloc")]
+    fn unwrap_cow(wrapper: Cow<'_, Self>) -> Cow<'_, Self::Inner>
+    where
+        Self: Clone + UnwrapRef,
+        Self::Inner: Clone,
+    {
+        match wrapper {
+            Cow::Borrowed(wrapper) => Cow::Borrowed(wrapper.unwrap_ref()),
+            Cow::Owned(wrapper) => Cow::Owned(wrapper.unwrap()),
+        }
     }
+    /// Wraps a copy-on-write slice of values in the wrapper type without copying the values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(Clone, PartialEq, Debug)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let numbers = [12, 13];
+    /// let borrowed: Cow<[NumberWrapper]> = NumberWrapper::wrap_cow_slice(Cow::Borrowed(&numbers));
+    /// assert!(matches!(borrowed, Cow::Borrowed(_)));
+    ///
+    /// let owned: Cow<[NumberWrapper]> = NumberWrapper::wrap_cow_slice(Cow::Owned(vec![12, 13]));
+    /// assert_eq!(borrowed, owned);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn wrap_cow_slice(inner: Cow<'_, [Self::Inner]>) -> Cow<'_, [Self]>
+    where
+        Self: Clone + WrapRef,
+        Self::Inner: Clone,
+    {
+        match inner {
+            Cow::Borrowed(inner) => Cow::Borrowed(Self::wrap_slice(inner)),
+            Cow::Owned(inner) => Cow::Owned(Self::wrap_vec(inner)),
+        }
+    }
+    /// Unwraps a copy-on-write slice of wrappers, exposing the underlying values without copying
+    /// them
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use trapper::prelude::*;
+    /// newtype!(#[derive(Clone)] type NumberWrapper(i32));
+    ///
+    /// # fn main() {
+    /// let wrappers = vec![NumberWrapper::wrap(12), NumberWrapper::wrap(13)];
+    /// let numbers: Cow<[i32]> = NumberWrapper::unwrap_cow_slice(Cow::Owned(wrappers));
+    ///
+    /// assert_eq!(&*numbers, &[12, 13]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn unwrap_cow_slice(wrappers: Cow<'_, [Self]>) -> Cow<'_, [Self::Inner]>
+    where
+        Self: Clone + UnwrapRef,
+        Self::Inner: Clone,
+    {
+        match wrappers {
+            Cow::Borrowed(wrappers) => Cow::Borrowed(Self::unwrap_slice(wrappers)),
+            Cow::Owned(wrappers) => Cow::Owned(Self::unwrap_vec(wrappers)),
+        }
+    }
+}
+
+/// Asserts at compile time that a wrapper has the same size and alignment as its inner type.
+///
+/// `newtype!` emits these assertions itself, so this is meant for manual implementations of
+/// [`Transparent`]. Both types must be sized.
+///
+/// # Examples
+///
+/// Outside of a function, the assertion checks concrete types when the crate is compiled.
+///
+/// ```
+/// use trapper::assert_transparent;
+///
+/// #[repr(transparent)]
+/// pub struct Meters(f64);
+///
+/// assert_transparent!(Meters, f64);
+/// ```
+///
+/// Inside an impl, `Self` can be used as the wrapper to check generic wrappers, which is done
+/// when the function containing the assertion is monomorphized.
+///
+/// ```
+/// use trapper::{assert_transparent, Transparent};
+///
+/// #[repr(transparent)]
+/// pub struct Tagged<T>(T);
+///
+/// unsafe impl<T> Transparent for Tagged<T> {
+///     type Inner = T;
+///
+///     fn wrap_ptr(inner: *const T) -> *const Self {
+///         assert_transparent!(Self, T);
+///         inner as *const Self
+///     }
+///     fn unwrap_ptr(wrapper: *const Self) -> *const T {
+///         assert_transparent!(Self, T);
+///         wrapper as *const T
+///     }
+/// }
+/// ```
+///
+/// Any other generic wrapper can be checked in a function body by starting the arguments with
+/// `inline`, which also checks it when the function is monomorphized. Without it, the assertion
+/// is an item of its own, which can't use the generic parameters of the function around it.
+///
+/// ```
+/// use trapper::assert_transparent;
+///
+/// #[repr(transparent)]
+/// pub struct Tagged<T>(T);
+///
+/// fn tag<T>(value: T) -> Tagged<T> {
+///     assert_transparent!(inline Tagged<T>, T);
+///     Tagged(value)
+/// }
+/// # fn main() { tag(1u8); }
+/// ```
+///
+/// Types with different layouts fail to compile.
+///
+/// ```compile_fail
+/// use trapper::assert_transparent;
+///
+/// pub struct Meters(f64, u8);
+///
+/// assert_transparent!(Meters, f64);
+/// ```
+///
+/// ```compile_fail
+/// use trapper::assert_transparent;
+///
+/// pub struct Tagged<T>(T, u8);
+///
+/// fn tag<T>(value: T) -> Tagged<T> {
+///     assert_transparent!(inline Tagged<T>, T);
+///     Tagged(value, 0)
+/// }
+/// # fn main() { tag(1u32); }
+/// ```
+#[macro_export]
+macro_rules! assert_transparent {
+    (Self, $inner:ty $(,)?) => {
+        const {
+            $crate::__assert_layout!(Self, $inner);
+        }
+    };
+    (inline $wrapper:ty, $inner:ty $(,)?) => {
+        const {
+            $crate::__assert_layout!($wrapper, $inner);
+        }
+    };
+    ($wrapper:ty, $inner:ty $(,)?) => {
+        const _: () = {
+            $crate::__assert_layout!($wrapper, $inner);
+        };
+    };
+}
+
+/// Asserts that the wrapper and inner type have the same size and alignment, as a statement in
+/// a constant context.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_layout {
+    ($wrapper:ty, $inner:ty) => {
+        assert!(
+            ::core::mem::size_of::<$wrapper>() == ::core::mem::size_of::<$inner>(),
+            concat!(
+                "`",
+                stringify!($wrapper),
+                "` doesn't have the same size as `",
+                stringify!($inner),
+                "`"
+            ),
+        );
+        assert!(
+            ::core::mem::align_of::<$wrapper>() == ::core::mem::align_of::<$inner>(),
+            concat!(
+                "`",
+                stringify!($wrapper),
+                "` doesn't have the same alignment as `",
+                stringify!($inner),
+                "`"
+            ),
+        );
+    };
+}
+
+pub use trapper_macro::{newtype, transparent, Wrapper};
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "alloc")]
+    pub use alloc::borrow::{Cow, ToOwned};
+
+    pub use crate::__with_alloc as with_alloc;
+}
+
+/// Expands to the given items if the `alloc` feature is enabled, or to an error explaining that
+/// they need it otherwise.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_alloc {
+    ($what:literal, $($items:tt)*) => {
+        $($items)*
+    };
+}
+
+/// Expands to the given items if the `alloc` feature is enabled, or to an error explaining that
+/// they need it otherwise.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_alloc {
+    ($what:literal, $($items:tt)*) => {
+        ::core::compile_error!(::core::concat!(
+            $what,
+            " requires the `alloc` feature of trapper"
+        ));
+    };
 }
 
-pub use trapper_macro::newtype;
+/// Re-exports the wrapper and capability traits, the `newtype` macro and the `Wrapper` derive so they can be imported at once.
+pub mod prelude {
+    pub use crate::{newtype, Transparent, UnwrapMut, UnwrapRef, WrapMut, WrapRef, Wrapper};
+}
 
 #[cfg(test)]
 mod tests {
-    use super::newtype;
+    use super::{newtype, transparent, Wrapper};
 
     newtype!(#[allow(dead_code)] type InMod(i32));
     newtype!(#[allow(dead_code)] type WithLifetimes<'a>(std::io::StderrLock<'a>));
+    newtype!(#[allow(dead_code)] #[newtype(io(Write))] type WriteLifetimes<'a>(std::io::StderrLock<'a>));
     newtype!(#[allow(dead_code)] type WithTypeParameters<T>(T));
     newtype!(#[allow(dead_code)] type WithBoth<'a, T>(&'a T));
     newtype!(#[allow(dead_code)] type WithClause<'a, T>(&'a T) where T: Default);
@@ -134,4 +1357,367 @@ mod tests {
     newtype! {
         type NoWhereClause<'a: 'b, 'b, T = i32>(&'b &'a T);
     }
+    newtype!(#[allow(dead_code)] type WithConst<const N: usize>([u8; N]));
+    newtype!(#[allow(dead_code)] type WithConstDefault<T, const N: usize = 4>([T; N]));
+    newtype!(#[allow(dead_code)] type WithConstBlock<const N: usize = { 2 + 2 }>([u8; N]));
+    newtype!(#[allow(dead_code)] type WithConstNegative<const N: i8 = -1>(u8));
+    newtype! {
+        #[allow(dead_code)]
+        type WithConstClause<'a, T, const N: usize = 8>(&'a [T; N]) where T: Copy + 'a, [T; N]: Default;
+    }
+    newtype!(#[allow(dead_code)] type WithConstNamed<const N: usize> where [u8; N]: Copy { value: [u8; N] });
+    newtype!(#[allow(dead_code)] type UnsizedStr(str));
+    newtype!(#[allow(dead_code)] type UnsizedSlice<T>([T]));
+    newtype!(#[allow(dead_code)] type UnsizedTraitObject(dyn std::fmt::Debug));
+    newtype!(#[allow(dead_code)] #[newtype(unsized)] type UnsizedPath(std::path::Path));
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(unsized, derive(Debug, PartialEq), borrow)]
+        type UnsizedOsStr(std::ffi::OsStr);
+    }
+    newtype!(#[allow(dead_code)] type MaybeUnsized<T: ?Sized>(T));
+    #[cfg(feature = "alloc")]
+    newtype! {
+        #[allow(dead_code)]
+        type OwnedPath(std::path::PathBuf) =>
+        #[allow(dead_code)]
+        type BorrowedPath(std::path::Path);
+    }
+    #[cfg(feature = "alloc")]
+    newtype! {
+        #[allow(dead_code)]
+        type OwnedPair(std::string::String) =>
+        #[allow(dead_code)]
+        type BorrowedPair(str);
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(skip(WrapRef, UnwrapRef))]
+        type SkipShared<T>(std::cell::Cell<T>);
+    }
+    newtype!(#[allow(dead_code)] #[newtype(skip(WrapMut), skip(UnwrapMut))] type SkipUnique(u32));
+    newtype!(#[allow(dead_code)] #[newtype(skip(UnwrapMut), borrow)] type SkipUniqueBorrow(u32));
+    #[allow(dead_code)]
+    fn not_empty<T>(slice: &[T]) -> Result<(), ()> {
+        if slice.is_empty() {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+    newtype!(#[allow(dead_code)] #[newtype(validate = not_empty, error = ())] type ValidatedUnsized<T>([T]));
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(validate = not_empty, error = ())]
+        type ValidatedSized<T>(std::vec::Vec<T>);
+    }
+    newtype!(#[allow(dead_code)] #[newtype(wrap = pub(crate))] type SealedSized<T>(T));
+    newtype!(#[allow(dead_code)] #[newtype(wrap = pub(self))] pub type SealedUnsized(str));
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(wrap = pub(crate), skip(WrapRef, UnwrapRef))]
+        type SealedSkip(std::cell::Cell<u32>);
+    }
+    newtype!(#[allow(dead_code)] type MaybeUnsizedClause<T>(T) where T: ?Sized + std::fmt::Debug);
+
+    #[allow(dead_code)]
+    #[derive(Wrapper)]
+    #[repr(transparent)]
+    struct DerivedTuple(i32);
+    #[allow(dead_code)]
+    #[derive(Wrapper)]
+    #[repr(transparent)]
+    struct DerivedNamed<'a, T: ?Sized> {
+        marker: core::marker::PhantomData<&'a ()>,
+        pinned: core::marker::PhantomPinned,
+        #[newtype(inner)]
+        inner: T,
+    }
+    #[allow(dead_code)]
+    #[derive(Wrapper)]
+    #[repr(transparent)]
+    struct DerivedUnsized([u8]);
+    #[allow(dead_code)]
+    #[derive(Wrapper)]
+    #[repr(transparent)]
+    struct DerivedOnlyMarker(core::marker::PhantomData<u8>);
+    #[derive(Default)]
+    struct Marker;
+    #[allow(dead_code)]
+    #[derive(Wrapper)]
+    #[repr(transparent)]
+    struct DerivedUserMarker(u64, Marker);
+    #[allow(dead_code)]
+    #[transparent]
+    struct AttrUserMarker(Marker, #[newtype(inner)] u64);
+    #[allow(dead_code)]
+    #[transparent]
+    struct AttrTuple(pub i32);
+    #[allow(dead_code)]
+    #[transparent]
+    #[repr(transparent)]
+    struct AttrRepr(i32);
+    #[allow(dead_code)]
+    #[transparent(skip(WrapRef, UnwrapRef))]
+    struct AttrSkip(std::cell::Cell<u32>);
+    #[allow(dead_code)]
+    #[transparent(validate = not_empty, error = ())]
+    struct AttrValidated<T> {
+        items: std::vec::Vec<T>,
+    }
+    #[allow(dead_code)]
+    #[transparent(wrap = pub(crate))]
+    struct AttrSealed<T: ?Sized>(T);
+    newtype!(#[allow(dead_code)] type WithMarker<T>(u64, core::marker::PhantomData<T>));
+    newtype! {
+        #[allow(dead_code)]
+        type WithMarkers<'a, T>(
+            pub &'a T,
+            core::marker::PhantomPinned,
+            (),
+            [u8; 0],
+        );
+    }
+    newtype!(#[allow(dead_code)] #[newtype(wrap = pub(crate))] type SealedMarker<T>(T, ()));
+    newtype!(#[allow(dead_code)] type UserMarker(u64, Marker));
+    newtype!(#[allow(dead_code)] type UserMarkerFirst(Marker, #[newtype(inner)] u64));
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(validate = not_empty, error = ())]
+        type ValidatedMarker<T>(std::vec::Vec<T>, core::marker::PhantomData<T>);
+    }
+    #[cfg(feature = "alloc")]
+    newtype! {
+        #[allow(dead_code)]
+        type OwnedMarker(std::string::String, ()) =>
+        #[allow(dead_code)]
+        type BorrowedMarker(str);
+    }
+    newtype!(#[allow(dead_code)] type Named { value: i32 });
+    newtype! {
+        #[allow(dead_code)]
+        pub type NamedWithClause<'a, T> where T: ?Sized + std::fmt::Debug {
+            /// the wrapped reference
+            pub(crate) value: &'a T,
+            kind: core::marker::PhantomData<T>,
+        }
+    }
+    newtype!(#[allow(dead_code)] type NamedUnsized<T> { value: [T] });
+    newtype!(#[allow(dead_code)] #[newtype(wrap = pub(crate))] type NamedSealed { value: u8 });
+    newtype!(#[allow(dead_code)] #[newtype(wrap = pub(crate))] type SealedField(pub(crate) u8));
+    #[cfg(feature = "alloc")]
+    newtype! {
+        #[allow(dead_code)]
+        type OwnedNamed { value: std::string::String } =>
+        #[allow(dead_code)]
+        type BorrowedNamed { value: str }
+    }
+    #[cfg(feature = "alloc")]
+    newtype! {
+        #[allow(dead_code)]
+        type ListFirst(u8);
+        #[allow(dead_code)]
+        type ListSecond<T>(T) where T: Copy impl {
+            #[allow(dead_code)]
+            fn get(&self) -> T {
+                self.0
+            }
+        }
+        #[allow(dead_code)]
+        type ListNamed { value: u8 } impl {}
+        #[allow(dead_code)]
+        type ListOwned(std::string::String) impl {
+            #[allow(dead_code)]
+            const EMPTY: &'static str = "";
+        } =>
+        #[allow(dead_code)]
+        type ListBorrowed(str) impl {};
+        #[allow(dead_code)]
+        type ListLast(u8)
+    }
+    newtype!(#[allow(dead_code)] #[newtype(crate = crate)] type CratePath(u8));
+    #[cfg(feature = "alloc")]
+    newtype! {
+        #![newtype(crate = super)]
+        #[allow(dead_code)]
+        type CrateDefault(u8);
+        #[allow(dead_code)]
+        #[newtype(crate = crate)]
+        type CrateOverride(u8);
+        #[allow(dead_code)]
+        type CrateOwned(std::string::String) =>
+        #[allow(dead_code)]
+        type CrateBorrowed(str);
+    }
+    #[allow(dead_code)]
+    #[derive(Wrapper)]
+    #[newtype(crate = crate, skip(WrapMut))]
+    #[repr(transparent)]
+    struct CrateDerived(u8);
+    #[allow(dead_code)]
+    #[transparent(crate = crate)]
+    struct CrateAttr(u8);
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default))]
+        type DeriveAll<T>(T);
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(derive(Clone, Copy, Debug))]
+        type DeriveRef<'a, T>(&'a T);
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug))]
+        type DeriveUnsized(str);
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(derive(Clone, Debug, Default), derive(PartialEq))]
+        type DeriveNamed<T> { value: T, marker: core::marker::PhantomData<T> }
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(validate = not_empty, error = (), derive(Clone, Debug))]
+        type DeriveValidated<T>(std::vec::Vec<T>);
+    }
+    #[allow(dead_code)]
+    #[transparent(derive(Clone, Debug))]
+    struct DeriveAttr<'a, T: ?Sized>(&'a T);
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(ops(Add, Sub, Mul, Div, Rem, BitAnd, BitOr, BitXor, Shl, Shr, Neg, Not))]
+        #[newtype(ops(AddAssign, SubAssign, MulAssign, DivAssign, RemAssign))]
+        #[newtype(ops(BitAndAssign, BitOrAssign, BitXorAssign, ShlAssign, ShrAssign))]
+        type OpsAll<T>(T);
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(ops_inner(Add, Sub, Mul, Div, Rem, BitAnd, BitOr, BitXor, Shl, Shr))]
+        #[newtype(ops_inner(AddAssign, SubAssign, MulAssign, DivAssign, RemAssign))]
+        #[newtype(ops_inner(BitAndAssign, BitOrAssign, BitXorAssign, ShlAssign, ShrAssign))]
+        type OpsInnerAll<T>(T);
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(ops(Add, Neg, AddAssign), ops_inner(Sub), scalar(f32, f64))]
+        type OpsScalar<T> { value: T, marker: core::marker::PhantomData<T> }
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(ops(Add, Not), scalar(u32))]
+        type OpsConcrete(u32);
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(ops_inner(Mul, Div, MulAssign), scalar(f64))]
+        type OpsInnerScalar(f64);
+    }
+    #[allow(dead_code)]
+    #[transparent(ops(Add, Sub), scalar(u8))]
+    struct OpsAttr<'a>(u8, core::marker::PhantomData<&'a ()>);
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(fmt(Display, Debug, LowerHex, UpperHex, Octal, Binary, LowerExp, UpperExp))]
+        type FmtAll<T>(T);
+    }
+    newtype!(#[allow(dead_code)] #[newtype(fmt(Debug), display = "<{:>8}>")] type FmtUnsized(str));
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(derive(Debug), display = "{}")]
+        type FmtNamed<'a, T: ?Sized> { value: &'a T }
+    }
+    #[allow(dead_code)]
+    #[derive(Wrapper)]
+    #[newtype(fmt(Display))]
+    #[repr(transparent)]
+    struct FmtDerived(u8);
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(io(Read, Write, BufRead, Seek), fmt(Write))]
+        type IoAll<T>(T);
+    }
+    newtype!(#[allow(dead_code)] #[newtype(io(Write))] type IoUnsized(dyn std::io::Write));
+    newtype!(#[allow(dead_code)] #[newtype(fmt(Write))] type FmtWrite(std::string::String));
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(io(Read, BufRead))]
+        type IoNamed<'a> { reader: &'a [u8] }
+    }
+    newtype!(#[allow(dead_code)] #[newtype(borrow)] type BorrowGeneric<T>(T));
+    newtype!(#[allow(dead_code)] #[newtype(borrow, derive(Hash))] type BorrowDerived(u64));
+    newtype!(#[allow(dead_code)] #[newtype(borrow)] type BorrowUnsized(str));
+    newtype!(#[allow(dead_code)] #[newtype(borrow(PartialEq, PartialOrd))] type BorrowFloat(f64));
+    newtype!(#[allow(dead_code)] #[newtype(borrow())] type BorrowNone(f32));
+    newtype!(#[allow(dead_code)] #[newtype(borrow)] type BorrowRef<'a, T>(&'a [T]));
+    newtype!(#[allow(dead_code)] #[newtype(borrow)] type BorrowParamRef<'a, T>(&'a T));
+    newtype!(#[allow(dead_code)] #[newtype(borrow)] type BorrowParamMut<'a, T>(&'a mut T));
+    newtype!(#[allow(dead_code)] #[newtype(borrow)] type BorrowBox<T>(std::boxed::Box<T>));
+    newtype!(#[allow(dead_code)] #[newtype(borrow)] type BorrowPin<T>(core::pin::Pin<T>));
+    newtype!(#[allow(dead_code)] #[newtype(borrow, wrap = pub(crate))] type BorrowSealed(u64));
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(borrow)]
+        type BorrowNamed<T> { id: std::vec::Vec<T> }
+    }
+    newtype!(#[allow(dead_code)] #[newtype(from, from_ref)] type ConvertGeneric<T>(T));
+    newtype!(#[allow(dead_code)] #[newtype(from, into, from_ref)] type ConvertConcrete(u64));
+    newtype!(#[allow(dead_code)] #[newtype(from, into, from_ref)] type ConvertVec<T>(std::vec::Vec<T>));
+    newtype!(#[allow(dead_code)] #[newtype(from_ref)] type ConvertUnsized<T>([T]));
+    newtype!(#[allow(dead_code)] #[newtype(from, into, from_ref)] type ConvertRef<'a>(&'a str));
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(validate = not_empty, error = (), into, from_ref)]
+        type ConvertValidated<T>(std::vec::Vec<T>);
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(validate = not_empty, error = (), from_ref)]
+        type ConvertValidatedUnsized<T>([T]);
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(from, into)]
+        type ConvertMarked<T>(u32, core::marker::PhantomData<T>);
+    }
+    newtype!(#[allow(dead_code)] #[newtype(deref_mut)] type DerefGeneric<T: ?Sized>(T));
+    newtype!(#[allow(dead_code)] #[newtype(deref, deref_mut)] type DerefBoth(std::string::String));
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(validate = not_empty, error = (), deref)]
+        type DerefValidated<T>([T]);
+    }
+    newtype!(#[allow(dead_code)] #[newtype(deref, wrap = pub(crate))] type DerefSealed(u32));
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(collection(
+            IntoIterator,
+            FromIterator,
+            Extend,
+            Index,
+            IndexMut,
+            Default,
+            Sum,
+            Product
+        ))]
+        type CollectionGeneric<T>(T);
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(collection(IntoIterator, FromIterator, Extend, Index, IndexMut, Default))]
+        type CollectionMap<K, V>(std::collections::HashMap<K, V>);
+    }
+    newtype!(#[allow(dead_code)] #[newtype(collection(IntoIterator, Index, IndexMut))] type CollectionUnsized<T>([T]));
+    newtype!(#[allow(dead_code)] #[newtype(collection(Sum, Product), derive(Default))] type CollectionNumber(u64));
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(validate = not_empty, error = (), collection(IntoIterator, Index))]
+        type CollectionValidated<T>(std::vec::Vec<T>);
+    }
+    newtype! {
+        #[allow(dead_code)]
+        #[newtype(collection(IntoIterator, FromIterator))]
+        type CollectionNamed<T> { items: std::vec::Vec<T>, marker: core::marker::PhantomData<T> }
+    }
 }
@@ -0,0 +1,75 @@
+use crate::impls::{bounded, is_uncovered, Definition};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+/// Generates the `borrow` conversions to the inner type, along with comparisons between the
+/// wrapper and the inner type. The comparison and hashing traits themselves are forwarded as
+/// derives, which keeps them consistent with the inner type as `Borrow` requires.
+pub fn expand(def: &Definition) -> TokenStream2 {
+    let options = def.options;
+    if !options.borrow {
+        return TokenStream2::new();
+    }
+    let Definition {
+        ident: name,
+        generics,
+        inner,
+        member,
+        ..
+    } = def;
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let self_ty = quote!(#name #type_generics);
+
+    // mutable access would let the inner value change without being checked, or is ruled out by
+    // skipping `UnwrapMut`
+    let as_mut = if options.validate.is_none()
+        && options.wrap.is_none()
+        && options.capabilities.unwrap_mut
+    {
+        Some(quote! {
+            impl #impl_generics ::core::convert::AsMut<#inner> for #self_ty #where_clause {
+                fn as_mut(&mut self) -> &mut #inner {
+                    &mut self.#member
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let eq = bounded(generics, inner, quote!(::core::cmp::PartialEq));
+    let (eq_impl_generics, _, eq_where_clause) = eq.split_for_impl();
+    // the reversed comparison would be implemented for an uncovered type parameter, which the
+    // orphan rules don't allow
+    let eq_rev = if is_uncovered(generics, inner) {
+        None
+    } else {
+        Some(quote! {
+            impl #eq_impl_generics ::core::cmp::PartialEq<#self_ty> for #inner #eq_where_clause {
+                fn eq(&self, other: &#self_ty) -> bool {
+                    <#inner as ::core::cmp::PartialEq>::eq(self, &other.#member)
+                }
+            }
+        })
+    };
+
+    quote! {
+        impl #impl_generics ::core::borrow::Borrow<#inner> for #self_ty #where_clause {
+            fn borrow(&self) -> &#inner {
+                &self.#member
+            }
+        }
+        impl #impl_generics ::core::convert::AsRef<#inner> for #self_ty #where_clause {
+            fn as_ref(&self) -> &#inner {
+                &self.#member
+            }
+        }
+        #as_mut
+        impl #eq_impl_generics ::core::cmp::PartialEq<#inner> for #self_ty #eq_where_clause {
+            fn eq(&self, other: &#inner) -> bool {
+                <#inner as ::core::cmp::PartialEq>::eq(&self.#member, other)
+            }
+        }
+        #eq_rev
+    }
+}
@@ -0,0 +1,214 @@
+use crate::impls::{bounded, Definition};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_quote, Generics, Ident, Lifetime};
+
+/// Returns the generics of the wrapper with added parameters for the items or indices of the
+/// inner collection, placing lifetimes first
+fn with_params(generics: &Generics, lifetime: Option<&Lifetime>, ty: Option<&Ident>) -> Generics {
+    let mut generics = generics.clone();
+    if let Some(lifetime) = lifetime {
+        generics.params.insert(0, parse_quote!(#lifetime));
+    }
+    if let Some(ty) = ty {
+        generics.params.push(parse_quote!(#ty));
+    }
+    generics
+}
+
+/// Generates the collection traits given to `collection(...)`, forwarded to the inner value and
+/// bounded on the inner type implementing them. `Default` is forwarded as a derive instead.
+pub fn expand(def: &Definition) -> TokenStream2 {
+    let Definition {
+        options,
+        ident: name,
+        generics,
+        inner,
+        member,
+        ..
+    } = def;
+    let (_, type_generics, _) = generics.split_for_impl();
+    let self_ty = quote!(#name #type_generics);
+    let lifetime = Lifetime::new("'__iter", Span::call_site());
+    let item = Ident::new("__Item", Span::call_site());
+    let index = Ident::new("__Index", Span::call_site());
+    // the inner value can't be mutated through validated or sealed wrappers
+    let checked = options.validate.is_some() || options.wrap.is_some();
+
+    let forwarded = options.collection.iter().map(|trait_name| {
+        let by_value = matches!(
+            trait_name.to_string().as_str(),
+            "FromIterator" | "Sum" | "Product"
+        );
+        if by_value && def.unsized_inner() {
+            return syn::Error::new(
+                trait_name.span(),
+                format!(
+                    "`{}` can't be forwarded for wrappers of unsized types",
+                    trait_name
+                ),
+            )
+            .to_compile_error();
+        }
+
+        match trait_name.to_string().as_str() {
+            "IntoIterator" => {
+                let owned = if def.unsized_inner() {
+                    None
+                } else {
+                    let owned = bounded(generics, inner, quote!(::core::iter::IntoIterator));
+                    let (impl_generics, _, where_clause) = owned.split_for_impl();
+                    Some(quote! {
+                        impl #impl_generics ::core::iter::IntoIterator for #self_ty #where_clause {
+                            type Item = <#inner as ::core::iter::IntoIterator>::Item;
+                            type IntoIter = <#inner as ::core::iter::IntoIterator>::IntoIter;
+
+                            fn into_iter(self) -> Self::IntoIter {
+                                <#inner as ::core::iter::IntoIterator>::into_iter(self.#member)
+                            }
+                        }
+                    })
+                };
+                let by_ref = with_params(generics, Some(&lifetime), None);
+                let shared = bounded(
+                    &by_ref,
+                    &parse_quote!(&#lifetime #inner),
+                    quote!(::core::iter::IntoIterator),
+                );
+                let (impl_generics, _, where_clause) = shared.split_for_impl();
+                let unique = if checked {
+                    None
+                } else {
+                    let unique = bounded(
+                        &by_ref,
+                        &parse_quote!(&#lifetime mut #inner),
+                        quote!(::core::iter::IntoIterator),
+                    );
+                    let (impl_generics, _, where_clause) = unique.split_for_impl();
+                    Some(quote! {
+                        impl #impl_generics ::core::iter::IntoIterator for &#lifetime mut #self_ty #where_clause {
+                            type Item = <&#lifetime mut #inner as ::core::iter::IntoIterator>::Item;
+                            type IntoIter = <&#lifetime mut #inner as ::core::iter::IntoIterator>::IntoIter;
+
+                            fn into_iter(self) -> Self::IntoIter {
+                                <&#lifetime mut #inner as ::core::iter::IntoIterator>::into_iter(&mut self.#member)
+                            }
+                        }
+                    })
+                };
+                quote! {
+                    #owned
+                    impl #impl_generics ::core::iter::IntoIterator for &#lifetime #self_ty #where_clause {
+                        type Item = <&#lifetime #inner as ::core::iter::IntoIterator>::Item;
+                        type IntoIter = <&#lifetime #inner as ::core::iter::IntoIterator>::IntoIter;
+
+                        fn into_iter(self) -> Self::IntoIter {
+                            <&#lifetime #inner as ::core::iter::IntoIterator>::into_iter(&self.#member)
+                        }
+                    }
+                    #unique
+                }
+            }
+            "FromIterator" => {
+                let construct = def.construct(quote!(
+                    <#inner as ::core::iter::FromIterator<#item>>::from_iter(iter)
+                ));
+                let generics = bounded(
+                    &with_params(generics, None, Some(&item)),
+                    inner,
+                    quote!(::core::iter::FromIterator<#item>),
+                );
+                let (impl_generics, _, where_clause) = generics.split_for_impl();
+                quote! {
+                    impl #impl_generics ::core::iter::FromIterator<#item> for #self_ty #where_clause {
+                        fn from_iter<I: ::core::iter::IntoIterator<Item = #item>>(iter: I) -> Self {
+                            #construct
+                        }
+                    }
+                }
+            }
+            "Extend" => {
+                let generics = bounded(
+                    &with_params(generics, None, Some(&item)),
+                    inner,
+                    quote!(::core::iter::Extend<#item>),
+                );
+                let (impl_generics, _, where_clause) = generics.split_for_impl();
+                quote! {
+                    impl #impl_generics ::core::iter::Extend<#item> for #self_ty #where_clause {
+                        fn extend<I: ::core::iter::IntoIterator<Item = #item>>(&mut self, iter: I) {
+                            <#inner as ::core::iter::Extend<#item>>::extend(&mut self.#member, iter)
+                        }
+                    }
+                }
+            }
+            "Index" => {
+                let generics = bounded(
+                    &with_params(generics, None, Some(&index)),
+                    inner,
+                    quote!(::core::ops::Index<#index>),
+                );
+                let (impl_generics, _, where_clause) = generics.split_for_impl();
+                quote! {
+                    impl #impl_generics ::core::ops::Index<#index> for #self_ty #where_clause {
+                        type Output = <#inner as ::core::ops::Index<#index>>::Output;
+
+                        fn index(&self, index: #index) -> &Self::Output {
+                            <#inner as ::core::ops::Index<#index>>::index(&self.#member, index)
+                        }
+                    }
+                }
+            }
+            "IndexMut" => {
+                let generics = bounded(
+                    &with_params(generics, None, Some(&index)),
+                    inner,
+                    quote!(::core::ops::IndexMut<#index>),
+                );
+                let (impl_generics, _, where_clause) = generics.split_for_impl();
+                quote! {
+                    impl #impl_generics ::core::ops::IndexMut<#index> for #self_ty #where_clause {
+                        fn index_mut(&mut self, index: #index) -> &mut Self::Output {
+                            <#inner as ::core::ops::IndexMut<#index>>::index_mut(&mut self.#member, index)
+                        }
+                    }
+                }
+            }
+            "Sum" | "Product" => {
+                let method = Ident::new(&trait_name.to_string().to_lowercase(), Span::call_site());
+                let trait_path = quote!(::core::iter::#trait_name);
+                let construct = def.construct(quote!(
+                    <#inner as #trait_path<#inner>>::#method(iter.map(|wrapper| wrapper.#member))
+                ));
+                let construct_ref = def.construct(quote!(
+                    <#inner as #trait_path<&#lifetime #inner>>::#method(
+                        iter.map(|wrapper| &wrapper.#member)
+                    )
+                ));
+                let owned = bounded(generics, inner, quote!(#trait_path<#inner>));
+                let (impl_generics, _, where_clause) = owned.split_for_impl();
+                let by_ref = bounded(
+                    &with_params(generics, Some(&lifetime), None),
+                    inner,
+                    quote!(#trait_path<&#lifetime #inner>),
+                );
+                let (ref_impl_generics, _, ref_where_clause) = by_ref.split_for_impl();
+                quote! {
+                    impl #impl_generics #trait_path<#self_ty> for #self_ty #where_clause {
+                        fn #method<I: ::core::iter::Iterator<Item = #self_ty>>(iter: I) -> Self {
+                            #construct
+                        }
+                    }
+                    impl #ref_impl_generics #trait_path<&#lifetime #self_ty> for #self_ty #ref_where_clause {
+                        fn #method<I: ::core::iter::Iterator<Item = &#lifetime #self_ty>>(iter: I) -> Self {
+                            #construct_ref
+                        }
+                    }
+                }
+            }
+            _ => unreachable!("collection traits are checked when parsing options"),
+        }
+    });
+
+    quote!(#(#forwarded)*)
+}
@@ -0,0 +1,98 @@
+use crate::impls::{is_uncovered, Definition};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{Generics, Ident, Lifetime};
+
+/// Returns the generics of the wrapper with an added lifetime parameter for reference conversions
+fn with_lifetime(generics: &Generics, lifetime: &Lifetime) -> Generics {
+    let mut generics = generics.clone();
+    generics.params.insert(0, syn::parse_quote!(#lifetime));
+    generics
+}
+
+/// Returns an error for a by-value conversion of an unsized inner type
+fn unsized_error(name: &Ident) -> TokenStream2 {
+    syn::Error::new(
+        name.span(),
+        format!("`{}` can't be used for wrappers of unsized types", name),
+    )
+    .to_compile_error()
+}
+
+/// Generates the `From` conversions given by `from`, `into` and `from_ref`, which are `TryFrom`
+/// conversions checking the value for validated wrappers
+pub fn expand(def: &Definition) -> TokenStream2 {
+    let Definition {
+        options,
+        ident: name,
+        generics,
+        inner,
+        member,
+        ..
+    } = def;
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let self_ty = quote!(#name #type_generics);
+    let krate = options.krate();
+    let lifetime = Lifetime::new("'__ref", Span::call_site());
+
+    let from = options.from.as_ref().map(|from| {
+        if def.unsized_inner() {
+            return unsized_error(from);
+        }
+        let construct = def.construct(quote!(inner));
+        quote! {
+            impl #impl_generics ::core::convert::From<#inner> for #self_ty #where_clause {
+                fn from(inner: #inner) -> Self {
+                    #construct
+                }
+            }
+        }
+    });
+
+    let into = options.into.as_ref().map(|into| {
+        if def.unsized_inner() {
+            return unsized_error(into);
+        }
+        if is_uncovered(generics, inner) {
+            return syn::Error::new(
+                into.span(),
+                "`into` can't be used when the inner type is a type parameter or a reference, `Box` or `Pin` of one, since the orphan rules don't allow implementing `From` for it",
+            )
+            .to_compile_error();
+        }
+        quote! {
+            impl #impl_generics ::core::convert::From<#self_ty> for #inner #where_clause {
+                fn from(wrapper: #self_ty) -> Self {
+                    wrapper.#member
+                }
+            }
+        }
+    });
+
+    let ref_generics = with_lifetime(generics, &lifetime);
+    let (ref_impl_generics, _, _) = ref_generics.split_for_impl();
+    let from_ref = options.from_ref.as_ref().map(|_| match &options.validate {
+        Some((_, error)) => quote! {
+            impl #ref_impl_generics ::core::convert::TryFrom<&#lifetime #inner> for &#lifetime #self_ty #where_clause {
+                type Error = #error;
+
+                fn try_from(inner: &#lifetime #inner) -> ::core::result::Result<Self, #error> {
+                    <#self_ty>::try_wrap_ref(inner)
+                }
+            }
+        },
+        None => quote! {
+            impl #ref_impl_generics ::core::convert::From<&#lifetime #inner> for &#lifetime #self_ty #where_clause {
+                fn from(inner: &#lifetime #inner) -> Self {
+                    <#self_ty as #krate::WrapRef>::wrap_ref(inner)
+                }
+            }
+        },
+    });
+
+    quote! {
+        #from
+        #into
+        #from_ref
+    }
+}
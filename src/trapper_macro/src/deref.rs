@@ -0,0 +1,42 @@
+use crate::impls::Definition;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+/// Generates `Deref` for `deref` and `DerefMut` along with it for `deref_mut`, going through the
+/// unwrapping capabilities
+pub fn expand(def: &Definition) -> TokenStream2 {
+    let Definition {
+        options,
+        ident: name,
+        generics,
+        inner,
+        ..
+    } = def;
+    if options.deref.is_none() && options.deref_mut.is_none() {
+        return TokenStream2::new();
+    }
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let self_ty = quote!(#name #type_generics);
+    let krate = options.krate();
+
+    let deref_mut = options.deref_mut.as_ref().map(|_| {
+        quote! {
+            impl #impl_generics ::core::ops::DerefMut for #self_ty #where_clause {
+                fn deref_mut(&mut self) -> &mut #inner {
+                    <Self as #krate::UnwrapMut>::unwrap_mut(self)
+                }
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics ::core::ops::Deref for #self_ty #where_clause {
+            type Target = #inner;
+
+            fn deref(&self) -> &#inner {
+                <Self as #krate::UnwrapRef>::unwrap_ref(self)
+            }
+        }
+        #deref_mut
+    }
+}
@@ -0,0 +1,129 @@
+use syn::spanned::Spanned;
+use syn::{
+    parse, Attribute, Data, Field, Fields, Index, Member, Meta, NestedMeta, Type, Visibility,
+};
+
+/// The field of a wrapper holding its inner value, along with its marker fields
+pub struct InnerField<'a> {
+    pub vis: &'a Visibility,
+    pub ty: &'a Type,
+    pub member: Member,
+    pub markers: Vec<Member>,
+}
+
+/// Returns the fields of a struct a wrapper is derived for
+pub fn struct_fields(data: &mut Data) -> parse::Result<&mut Fields> {
+    match data {
+        Data::Struct(data) => Ok(&mut data.fields),
+        Data::Enum(data) => Err(parse::Error::new(
+            data.enum_token.span(),
+            "`Wrapper` can only be derived for structs",
+        )),
+        Data::Union(data) => Err(parse::Error::new(
+            data.union_token.span(),
+            "`Wrapper` can only be derived for structs",
+        )),
+    }
+}
+
+/// Removes the `#[newtype(inner)]` attributes from the fields, returning the index of the field
+/// it was on, or of the first field without one. Returns `None` if there are no fields.
+pub fn take_inner(fields: &mut Fields) -> parse::Result<Option<usize>> {
+    let mut inner = None;
+    for (index, field) in fields.iter_mut().enumerate() {
+        let mut result = Ok(());
+        field.attrs.retain(|attr| {
+            if !attr.path.is_ident("newtype") {
+                return true;
+            }
+            if result.is_ok() {
+                result = check_inner(attr);
+                if result.is_ok() && inner.replace(index).is_some() {
+                    result = Err(parse::Error::new_spanned(
+                        attr,
+                        "only one field can be marked with `#[newtype(inner)]`",
+                    ));
+                }
+            }
+            false
+        });
+        result?;
+    }
+    Ok(inner.or_else(|| fields.iter().next().map(|_| 0)))
+}
+
+/// Checks that a `newtype` attribute on a field is `#[newtype(inner)]`
+fn check_inner(attr: &Attribute) -> parse::Result<()> {
+    match attr.parse_meta()? {
+        Meta::List(list) => match list.nested.iter().collect::<Vec<_>>().as_slice() {
+            [NestedMeta::Meta(Meta::Word(word))] if word == "inner" => Ok(()),
+            _ => Err(parse::Error::new_spanned(
+                &list.nested,
+                "expected `inner`, the only option for fields",
+            )),
+        },
+        meta => Err(parse::Error::new_spanned(
+            meta,
+            "expected `#[newtype(inner)]`",
+        )),
+    }
+}
+
+/// Returns the wrapped field at the index, with the other fields as its markers
+pub fn inner_field(fields: &Fields, index: usize) -> InnerField<'_> {
+    let mut inner = None;
+    let mut markers = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let member = member(i, field);
+        if i == index {
+            inner = Some((field, member));
+        } else {
+            markers.push(member);
+        }
+    }
+    let (field, member) = inner.expect("the wrapped field is one of the fields");
+    InnerField {
+        vis: &field.vis,
+        ty: &field.ty,
+        member,
+        markers,
+    }
+}
+
+/// Returns how the field at the index is accessed
+fn member(index: usize, field: &Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index {
+            index: index as u32,
+            span: field.span(),
+        }),
+    }
+}
+
+/// Returns whether the attributes include `#[repr(transparent)]`, rejecting any other
+/// representation hints
+pub fn is_transparent(attrs: &[Attribute]) -> parse::Result<bool> {
+    let mut transparent = false;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("repr")) {
+        let hints = match attr.parse_meta()? {
+            Meta::List(list) => list.nested,
+            meta => {
+                return Err(parse::Error::new(
+                    meta.span(),
+                    "expected `#[repr(transparent)]`",
+                ))
+            }
+        };
+        for hint in hints {
+            match &hint {
+                NestedMeta::Meta(Meta::Word(word)) if word == "transparent" => transparent = true,
+                _ => return Err(parse::Error::new(
+                    hint.span(),
+                    "conflicting representation hint, wrappers can only be `#[repr(transparent)]`",
+                )),
+            }
+        }
+    }
+    Ok(transparent)
+}
@@ -0,0 +1,60 @@
+use crate::impls::{bounded, Definition};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+/// Generates the formatting traits given to `fmt(...)`, which format the inner value as is or
+/// write to it in the case of `Write`, and the `Display` impl given by `display = "..."`
+pub fn expand(def: &Definition) -> TokenStream2 {
+    let Definition {
+        ident: name,
+        generics,
+        inner,
+        member,
+        ..
+    } = def;
+    let (_, type_generics, _) = generics.split_for_impl();
+
+    let forwarded = def.options.fmt.iter().map(|fmt| {
+        let generics = bounded(generics, inner, quote!(::core::fmt::#fmt));
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        if fmt == "Write" {
+            return quote! {
+                impl #impl_generics ::core::fmt::Write for #name #type_generics #where_clause {
+                    fn write_str(&mut self, s: &str) -> ::core::fmt::Result {
+                        <#inner as ::core::fmt::Write>::write_str(&mut self.#member, s)
+                    }
+                    fn write_char(&mut self, c: char) -> ::core::fmt::Result {
+                        <#inner as ::core::fmt::Write>::write_char(&mut self.#member, c)
+                    }
+                    fn write_fmt(&mut self, args: ::core::fmt::Arguments<'_>) -> ::core::fmt::Result {
+                        <#inner as ::core::fmt::Write>::write_fmt(&mut self.#member, args)
+                    }
+                }
+            };
+        }
+        quote! {
+            impl #impl_generics ::core::fmt::#fmt for #name #type_generics #where_clause {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    <#inner as ::core::fmt::#fmt>::fmt(&self.#member, f)
+                }
+            }
+        }
+    });
+
+    let display = def.options.display.as_ref().map(|format| {
+        let generics = bounded(generics, inner, quote!(::core::fmt::Display));
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics ::core::fmt::Display for #name #type_generics #where_clause {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::write!(f, #format, &self.#member)
+                }
+            }
+        }
+    });
+
+    quote! {
+        #(#forwarded)*
+        #display
+    }
+}
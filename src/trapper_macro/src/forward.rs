@@ -0,0 +1,150 @@
+use crate::impls::{bounded, Definition};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Member, Type};
+
+/// Generates the traits given to `derive(...)`, forwarded to the inner value and bounded on the
+/// inner type implementing them instead of on every type parameter
+pub fn expand(def: &Definition) -> TokenStream2 {
+    let Definition {
+        ident: name,
+        generics,
+        inner: inner_ty,
+        member,
+        ..
+    } = def;
+
+    let derives = def.options.derives.iter().map(|derive| {
+        let (bound, body) = match derive.to_string().as_str() {
+            "Clone" => {
+                let clone = def.construct(quote!(::core::clone::Clone::clone(&self.#member)));
+                (
+                    quote!(::core::clone::Clone),
+                    quote! {
+                        fn clone(&self) -> Self {
+                            #clone
+                        }
+                    },
+                )
+            }
+            "Copy" => (quote!(::core::marker::Copy), quote!()),
+            "PartialEq" => (
+                quote!(::core::cmp::PartialEq),
+                quote! {
+                    fn eq(&self, other: &Self) -> bool {
+                        ::core::cmp::PartialEq::eq(&self.#member, &other.#member)
+                    }
+                },
+            ),
+            "Eq" => (quote!(::core::cmp::Eq), quote!()),
+            "PartialOrd" => (
+                quote!(::core::cmp::PartialOrd),
+                quote! {
+                    fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                        ::core::cmp::PartialOrd::partial_cmp(&self.#member, &other.#member)
+                    }
+                },
+            ),
+            "Ord" => (
+                quote!(::core::cmp::Ord),
+                quote! {
+                    fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                        ::core::cmp::Ord::cmp(&self.#member, &other.#member)
+                    }
+                },
+            ),
+            "Hash" => (
+                quote!(::core::hash::Hash),
+                quote! {
+                    fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                        ::core::hash::Hash::hash(&self.#member, state)
+                    }
+                },
+            ),
+            "Debug" => {
+                let type_name = name.to_string();
+                let debug = match member {
+                    Member::Named(field) => {
+                        let field_name = field.to_string();
+                        quote!(f.debug_struct(#type_name).field(#field_name, &&self.#member).finish())
+                    }
+                    Member::Unnamed(_) => {
+                        quote!(f.debug_tuple(#type_name).field(&&self.#member).finish())
+                    }
+                };
+                (
+                    quote!(::core::fmt::Debug),
+                    quote! {
+                        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                            #debug
+                        }
+                    },
+                )
+            }
+            "Default" => {
+                let default = def.construct(quote!(::core::default::Default::default()));
+                (
+                    quote!(::core::default::Default),
+                    quote! {
+                        fn default() -> Self {
+                            #default
+                        }
+                    },
+                )
+            }
+            _ => unreachable!("derives are checked when parsing options"),
+        };
+
+        // by-value traits can't be bounded on an unsized type, which would be a trivially false
+        // bound for concrete wrappers
+        if def.unsized_inner() && (derive == "Clone" || derive == "Copy" || derive == "Default") {
+            return syn::Error::new(
+                derive.span(),
+                format!("`{}` can't be derived for wrappers of unsized types", derive),
+            )
+            .to_compile_error();
+        }
+
+        // floats are the usual inner types without a total order, which would otherwise only
+        // fail on the trivially false bound
+        if is_float(inner_ty) && (derive == "Eq" || derive == "Ord" || derive == "Hash") {
+            let hint = if def.options.borrow {
+                ", so `borrow` has to list the comparisons it does implement, like `borrow(PartialEq, PartialOrd)`"
+            } else {
+                ""
+            };
+            return syn::Error::new(
+                derive.span(),
+                format!(
+                    "`{}` can't be forwarded to `{}`, which doesn't implement it{}",
+                    derive,
+                    quote!(#inner_ty),
+                    hint
+                ),
+            )
+            .to_compile_error();
+        }
+
+        let generics = bounded(generics, inner_ty, bound.clone());
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics #bound for #name #type_generics #where_clause {
+                #body
+            }
+        }
+    });
+
+    quote!(#(#derives)*)
+}
+
+/// Returns whether the type is one of the primitive floating point types
+fn is_float(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => {
+            path.qself.is_none() && (path.path.is_ident("f32") || path.path.is_ident("f64"))
+        }
+        Type::Paren(paren) => is_float(&paren.elem),
+        Type::Group(group) => is_float(&group.elem),
+        _ => false,
+    }
+}
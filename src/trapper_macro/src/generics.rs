@@ -0,0 +1,79 @@
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::ToTokens;
+use syn::punctuated::Punctuated;
+use syn::{
+    parse, Attribute, ConstParam, Expr, ExprVerbatim, GenericParam, Generics, Lifetime,
+    LifetimeDef, Token, TypeParam,
+};
+
+/// Parses the generic parameters of a wrapper, without its where clause. This is the same as
+/// `Generics::parse`, except for const parameter defaults, which syn would otherwise try to
+/// continue as a comparison with the tokens after the closing `>`.
+pub fn parse_generics(input: parse::ParseStream) -> parse::Result<Generics> {
+    if !input.peek(Token![<]) {
+        return Ok(Generics::default());
+    }
+    let lt_token = input.parse()?;
+    let mut params = Punctuated::new();
+    while !input.peek(Token![>]) {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let param = if input.peek(Lifetime) {
+            GenericParam::Lifetime(LifetimeDef {
+                attrs,
+                ..input.parse()?
+            })
+        } else if input.peek(Token![const]) {
+            GenericParam::Const(ConstParam {
+                attrs,
+                ..input.call(parse_const_param)?
+            })
+        } else {
+            GenericParam::Type(TypeParam {
+                attrs,
+                ..input.parse()?
+            })
+        };
+        params.push_value(param);
+        if input.peek(Token![>]) {
+            break;
+        }
+        params.push_punct(input.parse()?);
+    }
+    let gt_token = input.parse()?;
+
+    Ok(Generics {
+        lt_token: Some(lt_token),
+        params,
+        gt_token: Some(gt_token),
+        where_clause: None,
+    })
+}
+
+/// Parses a const parameter, whose default can only be a literal, an identifier or a block
+fn parse_const_param(input: parse::ParseStream) -> parse::Result<ConstParam> {
+    let const_token = input.parse()?;
+    let ident = input.parse()?;
+    let colon_token = input.parse()?;
+    let ty = input.parse()?;
+    let (eq_token, default) = if input.peek(Token![=]) {
+        let eq_token = input.parse()?;
+        let mut tts = TokenStream2::new();
+        if input.peek(Token![-]) {
+            input.parse::<Token![-]>()?.to_tokens(&mut tts);
+        }
+        input.parse::<TokenTree>()?.to_tokens(&mut tts);
+        (Some(eq_token), Some(Expr::Verbatim(ExprVerbatim { tts })))
+    } else {
+        (None, None)
+    };
+
+    Ok(ConstParam {
+        attrs: Vec::new(),
+        const_token,
+        ident,
+        colon_token,
+        ty,
+        eq_token,
+        default,
+    })
+}
@@ -0,0 +1,355 @@
+use crate::borrow;
+use crate::collection;
+use crate::convert;
+use crate::deref;
+use crate::fmt;
+use crate::forward;
+use crate::io;
+use crate::ops;
+use crate::options::Options;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_quote, GenericArgument, GenericParam, Generics, Ident, Member, PathArguments,
+    TraitBoundModifier, Type, TypeParamBound, Visibility, WherePredicate,
+};
+
+/// The parts of a wrapper struct needed to implement the wrapper traits for it
+pub struct Definition<'a> {
+    pub options: &'a Options,
+    pub vis: &'a Visibility,
+    pub ident: &'a Ident,
+    pub generics: &'a Generics,
+    /// The type of the wrapped value
+    pub inner: &'a Type,
+    /// The field holding the wrapped value
+    pub member: Member,
+    /// The zero-sized fields next to the wrapped value, filled with their default when wrapping
+    pub markers: Vec<Member>,
+}
+
+impl<'a> Definition<'a> {
+    /// Returns whether the inner type is unsized, either given with `unsized` or known from its
+    /// syntax
+    pub fn unsized_inner(&self) -> bool {
+        self.options.unsized_inner || is_unsized(self.inner)
+    }
+
+    /// Returns an expression constructing the wrapper from an inner value
+    pub fn construct(&self, inner: TokenStream2) -> TokenStream2 {
+        let name = self.ident;
+        let member = &self.member;
+        let markers = &self.markers;
+        quote! {
+            #name {
+                #member: #inner,
+                #(#markers: ::core::default::Default::default(),)*
+            }
+        }
+    }
+}
+
+/// Returns whether the type is syntactically known to be dynamically sized
+pub fn is_unsized(ty: &Type) -> bool {
+    match ty {
+        Type::Slice(_) | Type::TraitObject(_) => true,
+        Type::Path(path) => path.qself.is_none() && path.path.is_ident("str"),
+        Type::Paren(paren) => is_unsized(&paren.elem),
+        Type::Group(group) => is_unsized(&group.elem),
+        _ => false,
+    }
+}
+
+/// Returns whether the type is one of the type parameters of the wrapper, or a reference, `Box` or
+/// `Pin` of one, which the orphan rules treat as uncovered and don't allow implementing foreign
+/// traits for
+pub fn is_uncovered(generics: &Generics, ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            if generics
+                .type_params()
+                .any(|param| path.path.is_ident(param.ident.clone()))
+            {
+                return true;
+            }
+            let segment = match path.path.segments.last() {
+                Some(segment) => segment.into_value(),
+                None => return false,
+            };
+            if segment.ident != "Box" && segment.ident != "Pin" {
+                return false;
+            }
+            match &segment.arguments {
+                PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                    match args.args.first().map(|arg| arg.into_value()) {
+                        Some(GenericArgument::Type(ty)) => is_uncovered(generics, ty),
+                        _ => false,
+                    }
+                }
+                _ => false,
+            }
+        }
+        Type::Reference(reference) => is_uncovered(generics, &reference.elem),
+        Type::Paren(paren) => is_uncovered(generics, &paren.elem),
+        Type::Group(group) => is_uncovered(generics, &group.elem),
+        _ => false,
+    }
+}
+
+/// Returns the generics of the wrapper with an added bound on the inner type
+pub fn bounded(generics: &Generics, inner: &Type, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(#inner: #bound));
+    generics
+}
+
+/// Returns whether any of the generic parameters are declared `?Sized`
+pub fn has_maybe_sized(generics: &Generics) -> bool {
+    let is_maybe = |bound: &TypeParamBound| match bound {
+        TypeParamBound::Trait(bound) => match bound.modifier {
+            TraitBoundModifier::Maybe(_) => true,
+            TraitBoundModifier::None => false,
+        },
+        TypeParamBound::Lifetime(_) => false,
+    };
+    let in_params = generics.params.iter().any(|param| match param {
+        GenericParam::Type(param) => param.bounds.iter().any(is_maybe),
+        _ => false,
+    });
+    let in_clause = generics.where_clause.iter().any(|clause| {
+        clause.predicates.iter().any(|predicate| match predicate {
+            WherePredicate::Type(predicate) => predicate.bounds.iter().any(is_maybe),
+            _ => false,
+        })
+    });
+    in_params || in_clause
+}
+
+/// Generates the wrapper trait implementations, along with the inherent methods replacing them
+/// for validated and sealed wrappers
+pub fn expand(def: &Definition) -> TokenStream2 {
+    let Definition {
+        options,
+        vis,
+        ident: name,
+        generics,
+        inner: inner_ty,
+        member,
+        ..
+    } = def;
+
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let krate = options.krate();
+
+    let sized = !def.unsized_inner();
+    let maybe_sized = has_maybe_sized(generics);
+    let mut sized_generics = (*generics).clone();
+    if maybe_sized {
+        sized_generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#inner_ty: Sized));
+    }
+    let sized_where_clause = &sized_generics.where_clause;
+    let construct = def.construct(quote!(inner));
+
+    let wrapper = if sized && options.validate.is_none() && options.wrap.is_none() {
+        Some(quote! {
+            unsafe impl #impl_generics #krate::Wrapper for #name #type_generics #sized_where_clause {
+                fn wrap(inner: Self::Inner) -> Self { #construct }
+                fn unwrap(self) -> Self::Inner { self.#member }
+            }
+        })
+    } else {
+        None
+    };
+
+    let validated = options.validate.as_ref().map(|(validate, error)| {
+        let by_ref = quote! {
+            impl #impl_generics #name #type_generics #where_clause {
+                /// Wraps a shared reference to the value if it passes validation, returning the
+                /// validation error otherwise
+                #vis fn try_wrap_ref(inner: &#inner_ty) -> ::core::result::Result<&Self, #error> {
+                    #validate(inner)?;
+                    ::core::result::Result::Ok(unsafe {
+                        &*<Self as #krate::Transparent>::wrap_ptr(inner)
+                    })
+                }
+            }
+        };
+        let by_value = if sized {
+            Some(quote! {
+                impl #impl_generics #name #type_generics #sized_where_clause {
+                    /// Wraps the value if it passes validation, returning the validation error
+                    /// otherwise
+                    #vis fn try_wrap(inner: #inner_ty) -> ::core::result::Result<Self, #error> {
+                        #validate(&inner)?;
+                        ::core::result::Result::Ok(#construct)
+                    }
+                    /// Wraps the value without validating it
+                    ///
+                    /// # Safety
+                    ///
+                    /// The value must pass validation.
+                    #vis unsafe fn wrap_unchecked(inner: #inner_ty) -> Self {
+                        #construct
+                    }
+                    /// Unwraps the wrapper, returning its inner value
+                    #vis fn unwrap(self) -> #inner_ty {
+                        self.#member
+                    }
+                }
+                impl #impl_generics ::core::convert::TryFrom<#inner_ty> for #name #type_generics #sized_where_clause {
+                    type Error = #error;
+
+                    fn try_from(inner: #inner_ty) -> ::core::result::Result<Self, #error> {
+                        Self::try_wrap(inner)
+                    }
+                }
+            })
+        } else {
+            None
+        };
+        quote!(#by_ref #by_value)
+    });
+
+    // casting a pointer to the wrapper into a trait object pointer is treated as an unsizing
+    // coercion, so the metadata has to be carried over with a transmute instead
+    let unwrap_ptr = if let Type::TraitObject(_) = inner_ty {
+        quote!(unsafe { ::core::mem::transmute::<*const Self, *const Self::Inner>(wrapper) })
+    } else {
+        quote!(wrapper as *const Self::Inner)
+    };
+
+    let capabilities = &options.capabilities;
+    let sealed = options.wrap.is_some();
+    let capability_impls = [
+        (capabilities.wrap_ref && !sealed, quote!(WrapRef)),
+        (capabilities.wrap_mut && !sealed, quote!(WrapMut)),
+        (capabilities.unwrap_ref, quote!(UnwrapRef)),
+        (capabilities.unwrap_mut && !sealed, quote!(UnwrapMut)),
+    ]
+    .iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, capability)| {
+        quote! {
+            unsafe impl #impl_generics #krate::#capability for #name #type_generics #where_clause {}
+        }
+    })
+    .collect::<Vec<_>>();
+
+    // sealed wrappers get the restricted conversions as inherent methods instead, so they can
+    // only be called where the given visibility allows
+    let sealed_impls = options.wrap.as_ref().map(|wrap_vis| {
+        let wrap_ref = if capabilities.wrap_ref {
+            Some(quote! {
+                /// Wraps a shared reference to the value in the wrapper type
+                #wrap_vis fn wrap_ref(inner: &#inner_ty) -> &Self {
+                    unsafe { &*<Self as #krate::Transparent>::wrap_ptr(inner) }
+                }
+            })
+        } else {
+            None
+        };
+        let wrap_mut = if capabilities.wrap_mut {
+            Some(quote! {
+                /// Wraps a unique reference to the value in the wrapper type
+                #wrap_vis fn wrap_mut(inner: &mut #inner_ty) -> &mut Self {
+                    unsafe { &mut *<Self as #krate::Transparent>::wrap_ptr_mut(inner) }
+                }
+            })
+        } else {
+            None
+        };
+        let unwrap_mut = if capabilities.unwrap_mut {
+            Some(quote! {
+                /// Unwraps a unique reference to the wrapper, exposing the underlying type
+                #wrap_vis fn unwrap_mut(&mut self) -> &mut #inner_ty {
+                    &mut self.#member
+                }
+            })
+        } else {
+            None
+        };
+        let by_value = if sized {
+            Some(quote! {
+                impl #impl_generics #name #type_generics #sized_where_clause {
+                    /// Wraps the value, returning a new instance of the wrapper
+                    #wrap_vis fn wrap(inner: #inner_ty) -> Self {
+                        #construct
+                    }
+                    /// Unwraps the wrapper, returning its inner value
+                    #vis fn unwrap(self) -> #inner_ty {
+                        self.#member
+                    }
+                }
+            })
+        } else {
+            None
+        };
+        quote! {
+            impl #impl_generics #name #type_generics #where_clause {
+                #wrap_ref
+                #wrap_mut
+                #unwrap_mut
+            }
+            #by_value
+        }
+    });
+
+    // generic wrappers are checked when the pointer casts are monomorphized, and concrete wrappers
+    // are checked right away as well, since their casts might never be used
+    let (layout_check, cast_check) = if sized && !maybe_sized {
+        let layout_check = if generics.params.is_empty() {
+            Some(quote!(#krate::assert_transparent!(#name, #inner_ty);))
+        } else {
+            None
+        };
+        (
+            layout_check,
+            Some(quote!(#krate::assert_transparent!(Self, #inner_ty);)),
+        )
+    } else {
+        (None, None)
+    };
+
+    let forwarded = forward::expand(def);
+    let ops = ops::expand(def);
+    let fmt = fmt::expand(def);
+    let io = io::expand(def);
+    let borrow = borrow::expand(def);
+    let convert = convert::expand(def);
+    let deref = deref::expand(def);
+    let collection = collection::expand(def);
+
+    quote! {
+        unsafe impl #impl_generics #krate::Transparent for #name #type_generics #where_clause {
+            type Inner = #inner_ty;
+
+            fn wrap_ptr(inner: *const Self::Inner) -> *const Self {
+                #cast_check
+                inner as *const Self
+            }
+            fn unwrap_ptr(wrapper: *const Self) -> *const Self::Inner {
+                #cast_check
+                #unwrap_ptr
+            }
+        }
+        #layout_check
+        #(#capability_impls)*
+        #wrapper
+        #validated
+        #sealed_impls
+        #forwarded
+        #ops
+        #fmt
+        #io
+        #borrow
+        #convert
+        #deref
+        #collection
+    }
+}
@@ -0,0 +1,103 @@
+use crate::impls::{bounded, Definition};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+/// Generates the `std::io` traits given to `io(...)`, forwarding every method with a specialized
+/// implementation to the inner value
+pub fn expand(def: &Definition) -> TokenStream2 {
+    let Definition {
+        ident: name,
+        generics,
+        inner,
+        member,
+        ..
+    } = def;
+    let (_, type_generics, _) = generics.split_for_impl();
+
+    let forwarded = def.options.io.iter().map(|io| {
+        let methods = match io.to_string().as_str() {
+            "Read" => quote! {
+                fn read(&mut self, buf: &mut [u8]) -> ::std::io::Result<usize> {
+                    <#inner as ::std::io::Read>::read(&mut self.#member, buf)
+                }
+                fn read_vectored(
+                    &mut self,
+                    bufs: &mut [::std::io::IoSliceMut<'_>],
+                ) -> ::std::io::Result<usize> {
+                    <#inner as ::std::io::Read>::read_vectored(&mut self.#member, bufs)
+                }
+                fn read_to_end(
+                    &mut self,
+                    buf: &mut ::std::vec::Vec<u8>,
+                ) -> ::std::io::Result<usize> {
+                    <#inner as ::std::io::Read>::read_to_end(&mut self.#member, buf)
+                }
+                fn read_to_string(
+                    &mut self,
+                    buf: &mut ::std::string::String,
+                ) -> ::std::io::Result<usize> {
+                    <#inner as ::std::io::Read>::read_to_string(&mut self.#member, buf)
+                }
+                fn read_exact(&mut self, buf: &mut [u8]) -> ::std::io::Result<()> {
+                    <#inner as ::std::io::Read>::read_exact(&mut self.#member, buf)
+                }
+            },
+            "Write" => quote! {
+                fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+                    <#inner as ::std::io::Write>::write(&mut self.#member, buf)
+                }
+                fn write_vectored(
+                    &mut self,
+                    bufs: &[::std::io::IoSlice<'_>],
+                ) -> ::std::io::Result<usize> {
+                    <#inner as ::std::io::Write>::write_vectored(&mut self.#member, bufs)
+                }
+                fn flush(&mut self) -> ::std::io::Result<()> {
+                    <#inner as ::std::io::Write>::flush(&mut self.#member)
+                }
+                fn write_all(&mut self, buf: &[u8]) -> ::std::io::Result<()> {
+                    <#inner as ::std::io::Write>::write_all(&mut self.#member, buf)
+                }
+                fn write_fmt(&mut self, args: ::core::fmt::Arguments<'_>) -> ::std::io::Result<()> {
+                    <#inner as ::std::io::Write>::write_fmt(&mut self.#member, args)
+                }
+            },
+            "BufRead" => quote! {
+                fn fill_buf(&mut self) -> ::std::io::Result<&[u8]> {
+                    <#inner as ::std::io::BufRead>::fill_buf(&mut self.#member)
+                }
+                fn consume(&mut self, amt: usize) {
+                    <#inner as ::std::io::BufRead>::consume(&mut self.#member, amt)
+                }
+                fn read_until(
+                    &mut self,
+                    byte: u8,
+                    buf: &mut ::std::vec::Vec<u8>,
+                ) -> ::std::io::Result<usize> {
+                    <#inner as ::std::io::BufRead>::read_until(&mut self.#member, byte, buf)
+                }
+                fn read_line(&mut self, buf: &mut ::std::string::String) -> ::std::io::Result<usize> {
+                    <#inner as ::std::io::BufRead>::read_line(&mut self.#member, buf)
+                }
+            },
+            "Seek" => quote! {
+                fn seek(&mut self, pos: ::std::io::SeekFrom) -> ::std::io::Result<u64> {
+                    <#inner as ::std::io::Seek>::seek(&mut self.#member, pos)
+                }
+                fn stream_position(&mut self) -> ::std::io::Result<u64> {
+                    <#inner as ::std::io::Seek>::stream_position(&mut self.#member)
+                }
+            },
+            _ => unreachable!("io traits are checked when parsing options"),
+        };
+        let generics = bounded(generics, inner, quote!(::std::io::#io));
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics ::std::io::#io for #name #type_generics #where_clause {
+                #methods
+            }
+        }
+    });
+
+    quote!(#(#forwarded)*)
+}
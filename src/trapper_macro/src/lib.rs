@@ -1,49 +1,186 @@
 extern crate proc_macro;
+mod borrow;
+mod collection;
+mod convert;
+mod deref;
+mod derive;
+mod fmt;
+mod forward;
+mod generics;
+mod impls;
+mod io;
+mod ops;
+mod options;
+
+use impls::Definition;
+use options::Options;
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parenthesized, parse, Attribute, Field, Generics, Ident, Token, Visibility};
+use syn::{
+    braced, parenthesized, parse, parse_quote, token, Attribute, DeriveInput, Field, Fields,
+    FieldsNamed, FieldsUnnamed, Generics, Ident, Path, Token, Visibility, WhereClause,
+};
 
 struct ItemNewType {
     pub attrs: Vec<Attribute>,
+    pub options: Options,
     pub vis: Visibility,
-    pub type_token: Token![type],
     pub ident: Ident,
     pub generics: Generics,
-    pub inner: Field,
-    pub semi: Option<Token![;]>,
+    /// The wrapped field and any zero-sized marker fields, either named or unnamed
+    pub fields: Fields,
+    /// The index of the wrapped field, which is the first unless marked with `#[newtype(inner)]`
+    pub inner: usize,
+    /// The contents of the `impl { ... }` block following the wrapper
+    pub methods: Option<TokenStream2>,
+    pub borrowed: Option<Box<ItemNewType>>,
 }
 
 impl parse::Parse for ItemNewType {
     fn parse(input: parse::ParseStream) -> parse::Result<Self> {
-        let attrs = input.call(Attribute::parse_outer)?;
+        let mut attrs = input.call(Attribute::parse_outer)?;
+        let options = Options::extract(&mut attrs)?;
         let vis = input.parse()?;
-        let type_token = input.parse()?;
+        input.parse::<Token![type]>()?;
         let ident = input.parse()?;
-        let generics = input.parse()?;
-        let inner = {
+        let generics = input.call(generics::parse_generics)?;
+        // named fields follow the where clause like they do in a struct, unnamed fields precede it
+        let mut where_clause = input.parse::<Option<WhereClause>>()?;
+        let content;
+        let (mut fields, span) = if where_clause.is_some() || input.peek(token::Brace) {
+            let brace = braced!(content in input);
+            let named = content.parse_terminated(Field::parse_named)?;
+            (
+                Fields::Named(FieldsNamed {
+                    brace_token: brace,
+                    named,
+                }),
+                brace.span,
+            )
+        } else if input.peek(token::Paren) {
+            let paren = parenthesized!(content in input);
+            let unnamed = content.parse_terminated(Field::parse_unnamed)?;
+            where_clause = input.parse()?;
+            (
+                Fields::Unnamed(FieldsUnnamed {
+                    paren_token: paren,
+                    unnamed,
+                }),
+                paren.span,
+            )
+        } else {
+            return Err(input.error(format!(
+                "expected the fields of `{}` in parentheses or braces",
+                ident
+            )));
+        };
+        let inner = match derive::take_inner(&mut fields)? {
+            Some(inner) => inner,
+            None => return Err(parse::Error::new(span, "expected a field to wrap")),
+        };
+        options.check_field(derive::inner_field(&fields, inner).vis)?;
+        let methods = if input.peek(Token![impl]) {
+            input.parse::<Token![impl]>()?;
             let content;
-            parenthesized!(content in input);
-            content.call(Field::parse_unnamed)?
+            braced!(content in input);
+            Some(content.parse()?)
+        } else {
+            None
+        };
+        let borrowed = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            let mut borrowed: ItemNewType = input.parse()?;
+            // borrowed forms are unsized like `str` and `Path`, which can't always be told from
+            // their syntax
+            borrowed.options.unsized_inner = true;
+            Some(Box::new(borrowed))
+        } else {
+            // items are separated by semicolons, which an `impl` block makes optional
+            if methods.is_some() || input.is_empty() {
+                input.parse::<Option<Token![;]>>()?;
+            } else {
+                input.parse::<Token![;]>()?;
+            }
+            None
         };
-        let where_clause = input.parse()?;
-        let semi = input.parse()?;
 
         Ok(ItemNewType {
             attrs,
+            options,
             vis,
-            type_token,
             ident,
             generics: Generics {
                 where_clause,
                 ..generics
             },
+            fields,
             inner,
-            semi,
+            methods,
+            borrowed,
         })
     }
 }
 
-/// Creates a new wrapper type. This type is transparent and implements `trapper::Wrapper`
+impl ItemNewType {
+    /// Returns the parts of the wrapper needed to implement the wrapper traits for it
+    fn definition(&self) -> Definition<'_> {
+        let field = derive::inner_field(&self.fields, self.inner);
+        Definition {
+            options: &self.options,
+            vis: &self.vis,
+            ident: &self.ident,
+            generics: &self.generics,
+            inner: field.ty,
+            member: field.member,
+            markers: field.markers,
+        }
+    }
+
+    /// Sets the crate path of the wrapper and its borrowed form, unless they were given their own
+    fn set_default_crate(&mut self, krate: &Path) {
+        self.options.krate.get_or_insert_with(|| krate.clone());
+        if let Some(borrowed) = &mut self.borrowed {
+            borrowed.set_default_crate(krate);
+        }
+    }
+
+    /// Returns the wrapped field
+    fn inner(&self) -> &Field {
+        self.fields.iter().nth(self.inner).unwrap()
+    }
+}
+
+/// The wrappers declared in a single `newtype!` invocation
+struct NewTypeList(Vec<ItemNewType>);
+
+impl parse::Parse for NewTypeList {
+    fn parse(input: parse::ParseStream) -> parse::Result<Self> {
+        let attrs = input.call(Attribute::parse_inner)?;
+        let krate = Options::default_crate(&attrs)?;
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            let mut item: ItemNewType = input.parse()?;
+            if let Some(krate) = &krate {
+                item.set_default_crate(krate);
+            }
+            items.push(item);
+        }
+        Ok(NewTypeList(items))
+    }
+}
+
+/// Creates a new wrapper type. This type is transparent and implements `trapper::Transparent`
+/// along with the `trapper::WrapRef`, `trapper::WrapMut`, `trapper::UnwrapRef` and
+/// `trapper::UnwrapMut` capabilities. If the inner type is sized, it also implements
+/// `trapper::Wrapper`, and the layouts of the wrapper and inner type are checked with
+/// `trapper::assert_transparent!`.
+///
+/// Slices, `str` and trait objects are detected as dynamically sized, so only the reference,
+/// pointer and (with the `alloc` feature) `Box`, `Rc` and `Arc` conversions of
+/// `trapper::Transparent` are available for them. Other unsized types, like `Path` or `OsStr`,
+/// can't be told apart from sized ones and need the `unsized` option. Generic wrappers with a
+/// `?Sized` parameter implement `trapper::Wrapper` whenever the inner type is sized.
 ///
 /// # Examples
 ///
@@ -56,37 +193,1011 @@ impl parse::Parse for ItemNewType {
 /// newtype!(pub type WithTypeParameters<T>(T));
 /// newtype!(pub type WithBoth<'a, T>(&'a T));
 /// newtype!(pub type WithClause<'a, T>(&'a T) where T: Default);
+/// newtype!(pub type Unsized(str));
+/// newtype!(pub type MaybeUnsized<T: ?Sized>(T));
+/// newtype!(#[newtype(unsized)] pub type UnsizedPath(std::path::Path));
+/// newtype!(pub type WithConst<const N: usize>([u8; N]));
+/// newtype!(pub type WithConstDefault<T, const N: usize = 4>([T; N]));
 /// newtype! {
 ///     /// a summary
 ///     pub type WithAttributes(i32);
 /// }
 /// # fn main() { }
 /// ```
+///
+/// # Multiple wrappers
+///
+/// Any number of wrappers can be declared in a single invocation, separated by semicolons. Each
+/// one can be followed by an `impl { ... }` block, which holds inherent items of the wrapper and
+/// takes its generics and where clause, and makes the semicolon after it optional.
+///
+/// ```
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     /// The id of a user
+///     #[derive(Clone, Copy, Debug, PartialEq)]
+///     pub type UserId(u64) impl {
+///         pub const ROOT: UserId = UserId(0);
+///     }
+///
+///     /// The id of a group
+///     pub type GroupId(u64);
+///
+///     pub type Tagged<T>(T) where T: Copy impl {
+///         pub fn get(&self) -> T {
+///             self.0
+///         }
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(UserId::ROOT, UserId::wrap(0));
+/// assert_eq!(GroupId::wrap(3).unwrap(), 3);
+/// assert_eq!(Tagged::wrap('a').get(), 'a');
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// use trapper::newtype;
+///
+/// newtype! {
+///     pub type UserId(u64)
+///     pub type GroupId(u64)
+/// }
+/// # fn main() { }
+/// ```
+///
+/// # Named fields
+///
+/// The wrapped field can be given a name by declaring it in braces, like in a struct with named
+/// fields. A where clause goes before the braces in that case.
+///
+/// ```
+/// use trapper::prelude::*;
+///
+/// newtype!(pub type Meters { pub value: f64 });
+/// newtype!(pub type Labeled<T> where T: Clone { label: T });
+///
+/// # fn main() {
+/// let Meters { value } = Meters::wrap(1.5);
+/// assert_eq!(value, 1.5);
+/// # }
+/// ```
+///
+/// # Marker fields
+///
+/// The wrapped field can be followed by zero-sized marker fields, like `PhantomData` for a type
+/// parameter that's otherwise unused. Marker fields must implement `Default`, which fills them in
+/// when wrapping, and `#[repr(transparent)]` rejects any that aren't zero-sized. The first field
+/// is the wrapped one, unless another is marked with `#[newtype(inner)]`.
+///
+/// ```
+/// use std::marker::PhantomData;
+/// use trapper::prelude::*;
+///
+/// pub struct User;
+///
+/// #[derive(Default)]
+/// pub struct Checked;
+///
+/// newtype!(pub type Id<T>(u64, PhantomData<T>));
+/// newtype!(pub type Score(Checked, #[newtype(inner)] u32));
+///
+/// # fn main() {
+/// let id: Id<User> = Id::wrap(7);
+/// assert_eq!(id.unwrap(), 7);
+/// assert_eq!(Score::wrap(3).unwrap(), 3);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// use trapper::newtype;
+///
+/// newtype!(pub type Point(f64, f64));
+/// # fn main() { }
+/// ```
+///
+/// # Options
+///
+/// Options are given in `#[newtype(...)]` attributes on the wrapper, which aren't passed on to
+/// the generated type.
+///
+/// * `unsized` - treats the inner type as unsized, which is only detected for slices, `str` and
+///   trait objects otherwise. This is needed for other dynamically sized types like `Path`,
+///   `OsStr`, `CStr` or custom ones.
+///
+/// * `skip(...)` - opts out of the listed capabilities, any of `WrapRef`, `WrapMut`, `UnwrapRef`
+///   and `UnwrapMut`. This is needed for wrappers where converting references in some direction
+///   is unsound, like a wrapper that mutates its inner value through shared references.
+///
+/// ```compile_fail
+/// use std::cell::Cell;
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(skip(WrapRef, UnwrapRef))]
+///     pub type Counter(Cell<u32>);
+/// }
+///
+/// # fn main() {
+/// let count = Cell::new(0);
+/// let counter: &Counter = Counter::wrap_ref(&count);
+/// # }
+/// ```
+///
+/// * `validate = path::to::fn` and `error = Type` - only wraps values that pass the validation
+///   function, which takes a reference to the inner value and returns `Result<(), Type>`. Instead
+///   of `trapper::Wrapper`, the wrapper gets `try_wrap`, `try_wrap_ref`, an unsafe
+///   `wrap_unchecked` and `unwrap` methods along with a `TryFrom` implementation for the inner
+///   type. Only the `UnwrapRef` capability is implemented, since the others could be used to
+///   wrap or write values that were never validated. The wrapped field must be private.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use trapper::prelude::*;
+///
+/// #[derive(Debug, PartialEq)]
+/// pub struct PortError;
+///
+/// fn check_port(port: &u16) -> Result<(), PortError> {
+///     if *port == 0 { Err(PortError) } else { Ok(()) }
+/// }
+///
+/// newtype! {
+///     #[newtype(validate = check_port, error = PortError)]
+///     #[derive(Debug, PartialEq)]
+///     pub type Port(u16);
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Port::try_wrap(0), Err(PortError));
+/// assert_eq!(Port::try_from(8080).map(Port::unwrap), Ok(8080));
+/// assert_eq!(Port::try_wrap_ref(&443).map(|port| *port.unwrap_ref()), Ok(443));
+/// # }
+/// ```
+///
+/// The unchecked conversions aren't available for validated wrappers.
+///
+/// ```compile_fail
+/// # use trapper::prelude::*;
+/// # pub struct PortError;
+/// # fn check_port(port: &u16) -> Result<(), PortError> { Ok(()) }
+/// newtype! {
+///     #[newtype(validate = check_port, error = PortError)]
+///     pub type Port(u16);
+/// }
+///
+/// # fn main() {
+/// let port = Port::wrap(0);
+/// # }
+/// ```
+///
+/// The wrapped field has to be private, since it could be used to construct the wrapper directly.
+///
+/// ```compile_fail
+/// # use trapper::prelude::*;
+/// # pub struct PortError;
+/// # fn check_port(port: &u16) -> Result<(), PortError> { Ok(()) }
+/// newtype! {
+///     #[newtype(validate = check_port, error = PortError)]
+///     pub type Port(pub u16);
+/// }
+/// # fn main() {}
+/// ```
+///
+/// * `wrap = pub(crate)` - seals construction and mutable unwrapping so they're only available
+///   with the given visibility, which can be `pub(self)` to only allow the defining module.
+///   Instead of `trapper::Wrapper`, `trapper::WrapRef`, `trapper::WrapMut` and
+///   `trapper::UnwrapMut`, the wrapper gets inherent `wrap`, `wrap_ref`, `wrap_mut` and
+///   `unwrap_mut` methods with that visibility. The `UnwrapRef` capability and an inherent
+///   `unwrap` method stay available with the wrapper's own visibility. This is useful for
+///   wrappers that act as proof that some check happened. The wrapped field must be private or
+///   have the same visibility as `wrap`.
+///
+/// ```
+/// mod auth {
+///     use trapper::prelude::*;
+///
+///     newtype! {
+///         #[newtype(wrap = pub(self))]
+///         pub type LoggedIn(u64);
+///     }
+///
+///     pub fn login(user: u64, password: &str) -> Option<LoggedIn> {
+///         if password == "hunter2" { Some(LoggedIn::wrap(user)) } else { None }
+///     }
+/// }
+///
+/// # fn main() {
+/// use trapper::prelude::*;
+///
+/// let proof = auth::login(12, "hunter2").unwrap();
+/// assert_eq!(*proof.unwrap_ref(), 12);
+/// # }
+/// ```
+///
+/// Code outside of the given visibility can't construct the wrapper.
+///
+/// ```compile_fail
+/// mod auth {
+///     use trapper::prelude::*;
+///
+///     newtype! {
+///         #[newtype(wrap = pub(self))]
+///         pub type LoggedIn(u64);
+///     }
+/// }
+///
+/// # fn main() {
+/// let forged = auth::LoggedIn::wrap(12);
+/// # }
+/// ```
+///
+/// Neither can it construct the wrapper through a field that's more visible than `wrap`.
+///
+/// ```compile_fail
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(wrap = pub(self))]
+///     pub type LoggedIn(pub(crate) u64);
+/// }
+/// # fn main() {}
+/// ```
+///
+/// * `derive(...)` - implements any of `Clone`, `Copy`, `PartialEq`, `Eq`, `PartialOrd`, `Ord`,
+///   `Hash`, `Debug` and `Default` by forwarding to the inner value. Unlike the standard derives,
+///   the impls are bounded on the inner type implementing the trait rather than on every type
+///   parameter, so a wrapper of `&'a T` is `Clone` and `Copy` for any `T`. `Default` isn't
+///   available for validated or sealed wrappers, and the by-value traits aren't available for
+///   unsized inner types.
+///
+/// ```
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(derive(Clone, Copy, PartialEq))]
+///     pub type Ref<'a, T>(&'a T);
+/// }
+///
+/// # fn main() {
+/// struct NotClone;
+///
+/// let value = NotClone;
+/// let wrapped = Ref::wrap(&value);
+/// let copied = wrapped;
+/// assert!(std::ptr::eq(*wrapped.clone().unwrap_ref(), *copied.unwrap_ref()));
+/// # }
+/// ```
+///
+/// * `borrow` and `borrow(...)` - implement `Borrow`, `AsRef` and `AsMut` of the inner type,
+///   along with `PartialEq` between the wrapper and the inner type in both directions. They also
+///   forward `PartialEq`, `Eq`, `PartialOrd`, `Ord` and `Hash`, or only the listed ones of them,
+///   like `derive(...)` does, so the wrapper compares and hashes exactly like its inner value,
+///   which lets maps keyed by the wrapper be looked up by the inner type. `borrow` can't be used
+///   when `UnwrapRef` is skipped, and `AsMut` isn't implemented when `UnwrapMut` is skipped or for
+///   validated or sealed wrappers. The comparison with the wrapper on the right isn't implemented
+///   when the inner type is a type parameter or a reference, `Box` or `Pin` of one, which the
+///   orphan rules don't allow.
+///
+///   The inner type has to implement every forwarded trait, so for inner types without a total
+///   order like `f64`, the comparisons can be limited to the ones it implements by listing them,
+///   as in `borrow(PartialEq, PartialOrd)`. Lookups by the inner type need the wrapper to compare
+///   and hash like it, so the traits the maps use, `Eq` and `Hash` or `Ord`, shouldn't be
+///   implemented any other way.
+///
+/// ```
+/// use std::collections::{BTreeMap, HashMap};
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(borrow)]
+///     pub type UserId(u64);
+///
+///     #[newtype(borrow(PartialEq, PartialOrd))]
+///     pub type Meters(f64);
+/// }
+///
+/// # fn main() {
+/// let mut names = HashMap::new();
+/// names.insert(UserId::wrap(42), "alice");
+/// assert_eq!(names.get(&42), Some(&"alice"));
+///
+/// let mut ages = BTreeMap::new();
+/// ages.insert(UserId::wrap(42), 30);
+/// assert_eq!(ages.get(&42), Some(&30));
+///
+/// assert!(UserId::wrap(42) == 42 && 42 == UserId::wrap(42));
+/// assert!(Meters::wrap(1.5) < Meters::wrap(2.0) && Meters::wrap(1.5) == 1.5);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// use std::cell::Cell;
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(skip(WrapRef, UnwrapRef), borrow(PartialEq))]
+///     pub type Counter(Cell<u32>);
+/// }
+/// # fn main() { }
+/// ```
+///
+/// * `from`, `into` and `from_ref` - implement `From<Inner>` for the wrapper, `From` of the
+///   wrapper for the inner type, and `From<&Inner>` for a reference to the wrapper through
+///   `wrap_ref`. Validated wrappers always implement `TryFrom<Inner>`, so `from` is rejected for
+///   them, and `from_ref` implements `TryFrom<&Inner>` for them instead, returning the validation
+///   error. `from` and `from_ref` aren't available for sealed wrappers, and `into` isn't available when the inner type is
+///   one of the wrapper's type parameters or a reference, `Box` or `Pin` of one, since the orphan
+///   rules don't allow it.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(from, into, from_ref)]
+///     pub type Meters(f64);
+///
+///     #[newtype(validate = check_even, error = &'static str, from_ref)]
+///     pub type Even(u32);
+/// }
+///
+/// fn check_even(value: &u32) -> Result<(), &'static str> {
+///     if value % 2 == 0 { Ok(()) } else { Err("odd") }
+/// }
+///
+/// # fn main() {
+/// let meters = Meters::from(2.5);
+/// let inner: f64 = meters.into();
+/// let by_ref: &Meters = (&inner).into();
+/// assert_eq!(*by_ref.unwrap_ref(), 2.5);
+///
+/// assert!(Even::try_from(4).is_ok());
+/// assert_eq!(<&Even>::try_from(&3).err(), Some("odd"));
+/// # }
+/// ```
+///
+/// A reference to a type parameter can't be converted into, since it's uncovered.
+///
+/// ```compile_fail
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(into)]
+///     pub type Tagged<'a, T>(&'a T);
+/// }
+/// # fn main() {}
+/// ```
+///
+/// Validated wrappers reject `from`, since they're converted with `TryFrom` instead.
+///
+/// ```compile_fail
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(validate = check_even, error = &'static str, from)]
+///     pub type Even(u32);
+/// }
+///
+/// fn check_even(value: &u32) -> Result<(), &'static str> {
+///     if value % 2 == 0 { Ok(()) } else { Err("odd") }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// * `deref` and `deref_mut` - implement `Deref` to the inner type through `unwrap_ref`, and
+///   `DerefMut` along with it through `unwrap_mut`. `deref_mut` isn't available for validated or
+///   sealed wrappers, since it would let the inner value change unchecked.
+///
+///   Keep in mind that methods of the wrapper shadow those of the inner type, including the ones
+///   of `Wrapper` and the capability traits when they're in scope, so `wrapper.unwrap_ref()`
+///   unwraps the wrapper even when the inner type has a method of the same name. Calls through
+///   the inner type like `Inner::method(&wrapper)` always reach it. For a wrapper declared as
+///   `type Name(String)`, `deref_mut` expands to:
+///
+/// ```ignore
+/// impl ::core::ops::Deref for Name {
+///     type Target = String;
+///
+///     fn deref(&self) -> &String {
+///         <Self as trapper::UnwrapRef>::unwrap_ref(self)
+///     }
+/// }
+/// impl ::core::ops::DerefMut for Name {
+///     fn deref_mut(&mut self) -> &mut String {
+///         <Self as trapper::UnwrapMut>::unwrap_mut(self)
+///     }
+/// }
+/// ```
+///
+/// ```
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(deref_mut)]
+///     pub type Name(String);
+/// }
+///
+/// # fn main() {
+/// let mut name = Name::wrap(String::from("trap"));
+/// name.push_str("per");
+/// assert_eq!(name.len(), 7);
+/// assert_eq!(name.to_uppercase(), "TRAPPER");
+/// # }
+/// ```
+///
+/// * `fmt(...)` and `display = "..."` - `fmt` implements any of `Display`, `Debug`, `LowerHex`,
+///   `UpperHex`, `Octal`, `Binary`, `LowerExp` and `UpperExp` by formatting the inner value as
+///   is, along with any flags like width and precision. This makes `Debug` transparent, printing
+///   `12` rather than `Count(12)`, so it can't be used along with `derive(Debug)`. `display`
+///   implements `Display` with a format string instead, formatting the inner value with its own
+///   `Display` impl in the string's placeholder.
+///
+/// ```
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(fmt(Debug, LowerHex), display = "user#{}")]
+///     pub type UserId(u32);
+/// }
+///
+/// # fn main() {
+/// let id = UserId::wrap(255);
+/// assert_eq!(format!("{:?}", id), "255");
+/// assert_eq!(format!("{:#06x}", id), "0x00ff");
+/// assert_eq!(id.to_string(), "user#255");
+/// # }
+/// ```
+///
+/// * `io(...)` - implements any of `Read`, `Write`, `BufRead` and `Seek` from `std::io` by
+///   forwarding to the inner value, including the vectored methods, so stream wrappers can be
+///   passed to anything taking a reader or writer. `fmt(Write)` does the same for
+///   `core::fmt::Write`. Neither is available for validated or sealed wrappers, since they mutate
+///   the inner value.
+///
+/// ```
+/// use std::io::{Cursor, Read, Write};
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(io(Read, Write))]
+///     pub type Stream(Cursor<Vec<u8>>);
+/// }
+///
+/// # fn main() {
+/// let mut stream = Stream::wrap(Cursor::new(Vec::new()));
+/// write!(stream, "hello").unwrap();
+/// stream.unwrap_mut().set_position(0);
+///
+/// let mut read = String::new();
+/// stream.read_to_string(&mut read).unwrap();
+/// assert_eq!(read, "hello");
+/// # }
+/// ```
+///
+/// * `collection(...)` - forwards any of `IntoIterator`, `FromIterator`, `Extend`, `Index`,
+///   `IndexMut`, `Default`, `Sum` and `Product` to the inner value, each bounded on the inner type
+///   implementing it. `IntoIterator` is implemented for the wrapper and for shared and unique
+///   references to it, `Index` and `IndexMut` take any index the inner type does, and `Sum` and
+///   `Product` combine wrappers or references to them into a wrapper. The traits constructing or
+///   mutating the wrapper aren't available for validated or sealed wrappers, and neither is
+///   iterating over unique references to them.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(collection(IntoIterator, FromIterator, Extend, Index, Default))]
+///     pub type Scores(HashMap<&'static str, u32>);
+///
+///     #[newtype(collection(Sum))]
+///     pub type Points(u32);
+/// }
+///
+/// # fn main() {
+/// let mut scores: Scores = vec![("alice", 3)].into_iter().collect();
+/// scores.extend(vec![("bob", 5)]);
+/// assert_eq!(scores["alice"], 3);
+///
+/// for (_, score) in &mut scores {
+///     *score *= 10;
+/// }
+/// let total: Points = scores.into_iter().map(|(_, score)| Points::wrap(score)).sum();
+/// assert_eq!(total.unwrap(), 80);
+/// # }
+/// ```
+///
+/// * `ops(...)`, `ops_inner(...)` and `scalar(...)` - forward operators from `core::ops` to the
+///   inner value, wrapping the result. `ops` implements the listed operators between two
+///   wrappers, and `ops_inner` between the wrapper and its inner type. Either takes any of `Add`,
+///   `Sub`, `Mul`, `Div`, `Rem`, `BitAnd`, `BitOr`, `BitXor`, `Shl` and `Shr` along with their
+///   `Assign` forms, and `ops` also takes the unary `Neg` and `Not`. The operators are implemented
+///   for references as well, like `&Meters + &Meters`. `scalar` takes a list of types the wrapper
+///   can be multiplied and divided by, which can also be multiplied by the wrapper. If the inner
+///   type is one of them, the operators `ops_inner` already implements for it are skipped. Each
+///   impl is bounded on the inner type implementing the operator. None of these are available for
+///   validated or sealed wrappers, since the results would be wrapped unchecked.
+///
+/// ```
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(derive(Clone, Copy, Debug, PartialEq))]
+///     #[newtype(ops(Add, Sub, AddAssign, Neg), ops_inner(Add), scalar(f64))]
+///     pub type Meters(f64);
+/// }
+///
+/// # fn main() {
+/// let mut distance = Meters::wrap(1.5) + Meters::wrap(2.5);
+/// distance += Meters::wrap(1.0);
+/// assert_eq!(distance, Meters::wrap(5.0));
+/// assert_eq!(&distance - &Meters::wrap(1.0), Meters::wrap(4.0));
+/// assert_eq!(-distance + 6.0, Meters::wrap(1.0));
+/// assert_eq!(distance * 2.0, 2.0 * distance);
+/// assert_eq!(distance / 2.0, Meters::wrap(2.5));
+/// # }
+/// ```
+///
+/// * `crate = path::to::trapper` - sets the path the generated code uses to refer to `trapper`,
+///   which is `trapper` by default. This is needed when `trapper` is re-exported from another
+///   crate and the crate using `newtype!` doesn't depend on it directly.
+///
+/// ```
+/// mod facade {
+///     pub use trapper;
+/// }
+///
+/// use facade::trapper::prelude::*;
+///
+/// newtype! {
+///     #[newtype(crate = facade::trapper)]
+///     pub type Meters(f64);
+/// }
+/// # fn main() { }
+/// ```
+///
+/// The crate path can also be set once for every wrapper in an invocation with
+/// `#![newtype(crate = path)]` at the start of it, which lets other crates wrap `newtype!` in
+/// their own macros.
+///
+/// ```
+/// mod facade {
+///     pub use trapper;
+///
+///     #[macro_export]
+///     macro_rules! ids {
+///         ($($items:tt)*) => {
+///             $crate::facade::trapper::newtype! {
+///                 #![newtype(crate = $crate::facade::trapper)]
+///                 $($items)*
+///             }
+///         };
+///     }
+/// }
+///
+/// ids! {
+///     pub type UserId(u64);
+///     pub type GroupId(u64);
+/// }
+///
+/// # fn main() {
+/// use facade::trapper::Wrapper;
+///
+/// assert_eq!(UserId::wrap(3).unwrap(), 3);
+/// # }
+/// ```
+///
+/// `#![newtype(...)]` is only available in `newtype!`, so `#[derive(Wrapper)]` and
+/// `#[transparent]` need `crate = path` on every item instead.
+///
+/// # Owned and borrowed pairs
+///
+/// A wrapper can be followed by `=>` and a second wrapper to declare an owned type and its
+/// borrowed form together, the same way `PathBuf` and `Path` are paired. The borrowed inner type
+/// must implement `ToOwned` with the owned inner type, which must implement `Borrow` of the
+/// borrowed inner type. The borrowed inner type is always treated as unsized, as if given the
+/// `unsized` option. This requires the `alloc` feature of `trapper`.
+///
+/// The owned type implements `Borrow`, `Deref` and `AsRef` of the borrowed type, the borrowed
+/// type implements `ToOwned` and `AsRef` of itself, and both convert to and from `Cow` of the
+/// borrowed type.
+///
+/// ```
+/// use std::borrow::Cow;
+/// use trapper::prelude::*;
+///
+/// newtype! {
+///     /// An owned username
+///     #[derive(Clone, Debug, PartialEq)]
+///     pub type UsernameBuf(String) =>
+///     /// A borrowed username
+///     #[derive(Debug, PartialEq)]
+///     pub type Username(str);
+/// }
+///
+/// # fn main() {
+/// fn greet(name: &Username) -> String {
+///     format!("hello {}", name.unwrap_ref())
+/// }
+///
+/// let owned = UsernameBuf::wrap("sydney".to_string());
+/// assert_eq!(greet(&owned), "hello sydney");
+///
+/// let borrowed: &Username = Username::wrap_ref("sydney");
+/// assert_eq!(borrowed.to_owned(), owned);
+///
+/// let cow: Cow<Username> = Cow::from(borrowed);
+/// assert_eq!(UsernameBuf::from(cow), owned);
+/// # }
+/// ```
+///
+/// Since either half can be converted to the other without going through its checks, neither
+/// can be validated or sealed.
+///
+/// ```compile_fail
+/// use trapper::prelude::*;
+///
+/// fn check_email(email: &str) -> Result<(), ()> {
+///     if email.contains('@') { Ok(()) } else { Err(()) }
+/// }
+///
+/// newtype! {
+///     pub type EmailBuf(String) =>
+///     #[newtype(validate = check_email, error = ())]
+///     pub type Email(str);
+/// }
+/// # fn main() {}
+/// ```
 #[proc_macro]
 pub fn newtype(item: TokenStream) -> TokenStream {
+    let items = syn::parse_macro_input!(item as NewTypeList);
+
+    let defs = items.0.iter().map(|item| match &item.borrowed {
+        Some(borrowed) => match expand_pair(item, borrowed) {
+            Ok(pair) => {
+                let owned = expand(item);
+                let borrowed = expand(borrowed);
+                quote!(#owned #borrowed #pair)
+            }
+            Err(err) => err.to_compile_error(),
+        },
+        None => expand(item),
+    });
+
+    TokenStream::from(quote!(#(#defs)*))
+}
+
+/// Implements the wrapper traits for an existing struct, the same way `newtype!` does for the
+/// types it creates. The struct must be `#[repr(transparent)]`, without any other representation
+/// hints, and can have tuple or named fields.
+///
+/// The wrapped field is the first field of the struct, unless another is marked with
+/// `#[newtype(inner)]`. The other fields are zero-sized markers, filled with their default value
+/// when wrapping.
+///
+/// Options are given in `#[newtype(...)]` attributes on the struct, the same as for `newtype!`.
+/// Since there's no invocation to set a default in, a crate path other than `trapper` has to be
+/// given with `crate = path` on every struct.
+///
+/// # Examples
+///
+/// ```
+/// use std::marker::PhantomData;
+/// use trapper::prelude::*;
+///
+/// #[derive(Wrapper)]
+/// #[repr(transparent)]
+/// pub struct Meters(f64);
+///
+/// #[derive(Wrapper)]
+/// #[repr(transparent)]
+/// pub struct Id<T> {
+///     kind: PhantomData<T>,
+///     #[newtype(inner)]
+///     value: u64,
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Meters::wrap(1.5).unwrap(), 1.5);
+///
+/// let id: Id<Meters> = Id::wrap(7);
+/// assert_eq!(*id.unwrap_ref(), 7);
+/// # }
+/// ```
+///
+/// Structs without `#[repr(transparent)]` are rejected, since their layout could differ from the
+/// wrapped field.
+///
+/// ```compile_fail
+/// use trapper::prelude::*;
+///
+/// #[derive(Wrapper)]
+/// pub struct Meters(f64);
+/// # fn main() { }
+/// ```
+///
+/// ```compile_fail
+/// use trapper::prelude::*;
+///
+/// #[derive(Wrapper)]
+/// #[repr(C)]
+/// pub struct Meters(f64);
+/// # fn main() { }
+/// ```
+///
+/// Only one field can hold data.
+///
+/// ```compile_fail
+/// use trapper::prelude::*;
+///
+/// #[derive(Wrapper)]
+/// #[repr(transparent)]
+/// pub struct Point {
+///     x: f64,
+///     y: f64,
+/// }
+/// # fn main() { }
+/// ```
+#[proc_macro_derive(Wrapper, attributes(newtype))]
+pub fn derive_wrapper(item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as DeriveInput);
+
+    let impls = derive::is_transparent(&input.attrs).and_then(|transparent| {
+        if !transparent {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "`Wrapper` can only be derived for `#[repr(transparent)]` structs",
+            ));
+        }
+        let options = Options::extract(&mut input.attrs.clone())?;
+        expand_derived(&mut input.clone(), &options)
+    });
+
+    TokenStream::from(impls.unwrap_or_else(|err| err.to_compile_error()))
+}
+
+/// Turns an ordinary struct into a wrapper type, adding `#[repr(transparent)]` and implementing
+/// the wrapper traits for it like `#[derive(Wrapper)]` does. Unlike `newtype!`, the struct stays
+/// an ordinary item, so it's understood by rustfmt and other tools.
+///
+/// This is the attribute form of `newtype!`, which can't share its name since attribute and
+/// function-like macros live in the same namespace. It takes the same options as arguments,
+/// described in the documentation of `newtype!`. Like `#[derive(Wrapper)]`, a crate path other
+/// than `trapper` has to be given with `crate = path` on every struct.
+///
+/// # Examples
+///
+/// ```
+/// use trapper::prelude::*;
+/// use trapper::transparent;
+///
+/// #[transparent]
+/// #[derive(Debug, PartialEq)]
+/// pub struct Meters(pub f64);
+///
+/// mod auth {
+///     use trapper::transparent;
+///
+///     #[transparent(wrap = pub(self))]
+///     pub struct LoggedIn {
+///         user: u64,
+///     }
+///
+///     pub fn login(user: u64, password: &str) -> Option<LoggedIn> {
+///         if password == "hunter2" { Some(LoggedIn::wrap(user)) } else { None }
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Meters::wrap(1.5), Meters(1.5));
+/// assert_eq!(auth::login(12, "hunter2").map(|proof| *proof.unwrap_ref()), Some(12));
+/// # }
+/// ```
+///
+/// Options are checked the same way they are for `newtype!`.
+///
+/// ```compile_fail
+/// use trapper::transparent;
+///
+/// #[transparent(wrap = pub(crate))]
+/// pub struct LoggedIn(pub u64);
+/// # fn main() { }
+/// ```
+#[proc_macro_attribute]
+pub fn transparent(args: TokenStream, item: TokenStream) -> TokenStream {
+    let options = syn::parse_macro_input!(args as Options);
+    let mut input = syn::parse_macro_input!(item as DeriveInput);
+
+    let impls = derive::is_transparent(&input.attrs).and_then(|transparent| {
+        if !transparent {
+            input.attrs.push(parse_quote!(#[repr(transparent)]));
+        }
+        expand_derived(&mut input, &options)
+    });
+
+    match impls {
+        Ok(impls) => TokenStream::from(quote!(#input #impls)),
+        Err(err) => {
+            let err = err.to_compile_error();
+            TokenStream::from(quote!(#input #err))
+        }
+    }
+}
+
+/// Generates the trait implementations for a wrapper struct defined outside of `newtype!`,
+/// removing the `#[newtype(inner)]` attribute from its fields
+fn expand_derived(input: &mut DeriveInput, options: &Options) -> syn::Result<TokenStream2> {
+    let DeriveInput {
+        vis,
+        ident,
+        generics,
+        data,
+        ..
+    } = input;
+    let fields = derive::struct_fields(data)?;
+    let inner = match derive::take_inner(fields)? {
+        Some(inner) => inner,
+        None => {
+            return Err(syn::Error::new(
+                ident.span(),
+                "`Wrapper` can only be derived for structs with a field to wrap",
+            ))
+        }
+    };
+    let field = derive::inner_field(fields, inner);
+    options.check_field(field.vis)?;
+    Ok(impls::expand(&Definition {
+        options,
+        vis,
+        ident,
+        generics,
+        inner: field.ty,
+        member: field.member,
+        markers: field.markers,
+    }))
+}
+
+/// Generates the wrapper type and its trait implementations
+fn expand(item: &ItemNewType) -> TokenStream2 {
     let ItemNewType {
         attrs: attributes,
         vis,
         ident: name,
-        inner,
+        fields,
         generics,
+        methods,
         ..
-    } = syn::parse_macro_input!(item as ItemNewType);
-
+    } = item;
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
 
-    let def = quote! {
+    let impls = impls::expand(&item.definition());
+    let definition = match fields {
+        Fields::Named(_) => quote!(#vis struct #name #generics #where_clause #fields),
+        _ => quote!(#vis struct #name #generics #fields #where_clause;),
+    };
+    let methods = methods.as_ref().map(|methods| {
+        quote! {
+            impl #impl_generics #name #type_generics #where_clause {
+                #methods
+            }
+        }
+    });
+
+    quote! {
         #(#attributes
         )*
         #[repr(transparent)]
-        #vis struct #name #generics (#inner) #where_clause;
-        unsafe impl #impl_generics trapper::Wrapper for #name #type_generics #where_clause {
-            type Inner = #inner;
+        #definition
+        #impls
+        #methods
+    }
+}
+
+/// Generates the impls linking an owned wrapper to its borrowed form, the same way `PathBuf` is
+/// linked to `Path`
+fn expand_pair(owned: &ItemNewType, borrowed: &ItemNewType) -> syn::Result<TokenStream2> {
+    for item in &[owned, borrowed] {
+        if !item.generics.params.is_empty() || item.generics.where_clause.is_some() {
+            return Err(syn::Error::new(
+                item.ident.span(),
+                "owned and borrowed newtype pairs can't be generic",
+            ));
+        }
+        // the conversions between the halves wrap values without validating them or checking
+        // where they happen
+        if item.options.validate.is_some() || item.options.wrap.is_some() {
+            return Err(syn::Error::new(
+                item.ident.span(),
+                "owned and borrowed newtype pairs can't be validated or sealed, since converting between them would skip the checks",
+            ));
+        }
+    }
+    if let Some(nested) = &borrowed.borrowed {
+        return Err(syn::Error::new(
+            nested.ident.span(),
+            "a borrowed newtype can't have its own borrowed form",
+        ));
+    }
+
+    let krate = owned.options.krate();
+    let owned_name = &owned.ident;
+    let borrowed_name = &borrowed.ident;
+    let borrowed_inner = &borrowed.inner().ty;
+    let owned_member = owned.definition().member;
+    let borrowed_member = borrowed.definition().member;
+    let to_owned = owned.definition().construct(quote!(
+        #krate::__private::ToOwned::to_owned(&self.#borrowed_member)
+    ));
+
+    let borrow = quote! {
+        impl ::core::borrow::Borrow<#borrowed_name> for #owned_name {
+            fn borrow(&self) -> &#borrowed_name {
+                let inner = ::core::borrow::Borrow::<#borrowed_inner>::borrow(&self.#owned_member);
+                unsafe { &*<#borrowed_name as #krate::Transparent>::wrap_ptr(inner) }
+            }
+        }
+        impl #krate::__private::ToOwned for #borrowed_name {
+            type Owned = #owned_name;
 
-            fn wrap(inner: Self::Inner) -> Self { Self(inner) }
-            fn unwrap(self) -> Self::Inner { self.0 }
+            fn to_owned(&self) -> #owned_name {
+                #to_owned
+            }
+        }
+        impl ::core::ops::Deref for #owned_name {
+            type Target = #borrowed_name;
+
+            fn deref(&self) -> &#borrowed_name {
+                ::core::borrow::Borrow::borrow(self)
+            }
+        }
+        impl ::core::convert::AsRef<#borrowed_name> for #owned_name {
+            fn as_ref(&self) -> &#borrowed_name {
+                ::core::borrow::Borrow::borrow(self)
+            }
+        }
+        impl ::core::convert::AsRef<#borrowed_name> for #borrowed_name {
+            fn as_ref(&self) -> &#borrowed_name {
+                self
+            }
+        }
+        impl ::core::convert::From<&#borrowed_name> for #owned_name {
+            fn from(borrowed: &#borrowed_name) -> #owned_name {
+                #krate::__private::ToOwned::to_owned(borrowed)
+            }
+        }
+    };
+    let cow = quote! {
+        impl<'a> ::core::convert::From<&'a #borrowed_name> for #krate::__private::Cow<'a, #borrowed_name> {
+            fn from(borrowed: &'a #borrowed_name) -> Self {
+                #krate::__private::Cow::Borrowed(borrowed)
+            }
+        }
+        impl<'a> ::core::convert::From<&'a #owned_name> for #krate::__private::Cow<'a, #borrowed_name> {
+            fn from(owned: &'a #owned_name) -> Self {
+                #krate::__private::Cow::Borrowed(::core::borrow::Borrow::borrow(owned))
+            }
+        }
+        impl<'a> ::core::convert::From<#owned_name> for #krate::__private::Cow<'a, #borrowed_name> {
+            fn from(owned: #owned_name) -> Self {
+                #krate::__private::Cow::Owned(owned)
+            }
+        }
+        impl<'a> ::core::convert::From<#krate::__private::Cow<'a, #borrowed_name>> for #owned_name {
+            fn from(cow: #krate::__private::Cow<'a, #borrowed_name>) -> #owned_name {
+                cow.into_owned()
+            }
         }
     };
 
-    TokenStream::from(def)
+    // `ToOwned` and `Cow` are only available with alloc
+    Ok(quote! {
+        #krate::__private::with_alloc! {
+            "an owned and borrowed newtype pair",
+            #borrow
+            #cow
+        }
+    })
 }
@@ -0,0 +1,296 @@
+use crate::impls::{bounded, Definition};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_quote, Generics, Ident, Lifetime, Type};
+
+/// The binary operators that can be forwarded, along with their assigning forms
+pub const BINARY: &[&str] = &[
+    "Add", "Sub", "Mul", "Div", "Rem", "BitAnd", "BitOr", "BitXor", "Shl", "Shr",
+];
+
+/// The unary operators that can be forwarded
+pub const UNARY: &[&str] = &["Neg", "Not"];
+
+/// Returns the generics of the wrapper with added lifetime parameters for reference operands
+fn with_lifetimes(generics: &Generics, lifetimes: &[&Lifetime]) -> Generics {
+    let mut generics = generics.clone();
+    for lifetime in lifetimes.iter().rev() {
+        generics.params.insert(0, parse_quote!(#lifetime));
+    }
+    generics
+}
+
+/// Generates the operators given to `ops(...)`, `ops_inner(...)` and `scalar(...)`, forwarded to
+/// the inner value
+pub fn expand(def: &Definition) -> TokenStream2 {
+    let options = def.options;
+    if options.ops.is_empty() && options.ops_inner.is_empty() && options.scalars.is_empty() {
+        return TokenStream2::new();
+    }
+    if def.unsized_inner() {
+        return syn::Error::new_spanned(
+            def.inner,
+            "operators can't be forwarded for wrappers of unsized types",
+        )
+        .to_compile_error();
+    }
+
+    let self_ops = options.ops.iter().map(|op| expand_op(def, op, None));
+    let inner_ops = options
+        .ops_inner
+        .iter()
+        .map(|op| expand_op(def, op, Some(def.inner)));
+    let scalars = options
+        .scalars
+        .iter()
+        .map(|scalar| expand_scalar(def, scalar));
+
+    quote! {
+        #(#self_ops)*
+        #(#inner_ops)*
+        #(#scalars)*
+    }
+}
+
+// the operator calls are fully qualified, since inference could otherwise pick the forwarded
+// impls themselves and overflow on generic wrappers
+
+/// Generates an operator between the wrapper and either itself or the given right hand side
+/// type, including its reference variants
+fn expand_op(def: &Definition, op: &Ident, rhs: Option<&Type>) -> TokenStream2 {
+    let Definition {
+        ident: name,
+        generics,
+        inner,
+        member,
+        ..
+    } = def;
+    let (_, type_generics, _) = generics.split_for_impl();
+    let self_ty = quote!(#name #type_generics);
+    let lhs_lt = Lifetime::new("'__lhs", Span::call_site());
+    let rhs_lt = Lifetime::new("'__rhs", Span::call_site());
+
+    let op_name = op.to_string();
+    let assign = op_name.ends_with("Assign");
+    let method = if assign {
+        format!(
+            "{}_assign",
+            op_name.trim_end_matches("Assign").to_lowercase()
+        )
+    } else {
+        op_name.to_lowercase()
+    };
+    let method = Ident::new(&method, op.span());
+    let trait_path = quote!(::core::ops::#op);
+
+    // the operand types and how the inner value is taken out of them
+    let (rhs_ty, rhs_value, rhs_ref_ty, rhs_ref_value) = match rhs {
+        None => (
+            quote!(#self_ty),
+            quote!(rhs.#member),
+            quote!(&#rhs_lt #self_ty),
+            quote!(&rhs.#member),
+        ),
+        Some(rhs) => (
+            quote!(#rhs),
+            quote!(rhs),
+            quote!(&#rhs_lt #rhs),
+            quote!(rhs),
+        ),
+    };
+
+    if assign {
+        let by_value = bounded(generics, inner, quote!(#trait_path<#inner>));
+        let (impl_generics, _, where_clause) = by_value.split_for_impl();
+        let by_ref = bounded(
+            &with_lifetimes(generics, &[&rhs_lt]),
+            inner,
+            quote!(#trait_path<&#rhs_lt #inner>),
+        );
+        let (ref_impl_generics, _, ref_where_clause) = by_ref.split_for_impl();
+        return quote! {
+            impl #impl_generics #trait_path<#rhs_ty> for #self_ty #where_clause {
+                fn #method(&mut self, rhs: #rhs_ty) {
+                    <#inner as #trait_path<#inner>>::#method(&mut self.#member, #rhs_value)
+                }
+            }
+            impl #ref_impl_generics #trait_path<#rhs_ref_ty> for #self_ty #ref_where_clause {
+                fn #method(&mut self, rhs: #rhs_ref_ty) {
+                    <#inner as #trait_path<&#rhs_lt #inner>>::#method(&mut self.#member, #rhs_ref_value)
+                }
+            }
+        };
+    }
+
+    if UNARY.contains(&op_name.as_str()) {
+        let construct = def.construct(quote!(<#inner as #trait_path>::#method(self.#member)));
+        let construct_ref = def.construct(quote!(
+            <&#lhs_lt #inner as #trait_path>::#method(&self.#member)
+        ));
+        let by_value = bounded(generics, inner, quote!(#trait_path<Output = #inner>));
+        let (impl_generics, _, where_clause) = by_value.split_for_impl();
+        let ref_generics = with_lifetimes(generics, &[&lhs_lt]);
+        let by_ref = bounded(
+            &ref_generics,
+            &parse_quote!(&#lhs_lt #inner),
+            quote!(#trait_path<Output = #inner>),
+        );
+        let (ref_impl_generics, _, ref_where_clause) = by_ref.split_for_impl();
+        return quote! {
+            impl #impl_generics #trait_path for #self_ty #where_clause {
+                type Output = #self_ty;
+
+                fn #method(self) -> #self_ty {
+                    #construct
+                }
+            }
+            impl #ref_impl_generics #trait_path for &#lhs_lt #self_ty #ref_where_clause {
+                type Output = #self_ty;
+
+                fn #method(self) -> #self_ty {
+                    #construct_ref
+                }
+            }
+        };
+    }
+
+    let construct = def.construct(quote!(
+        <#inner as #trait_path<#inner>>::#method(self.#member, #rhs_value)
+    ));
+    let construct_ref = def.construct(quote!(
+        <&#lhs_lt #inner as #trait_path<&#rhs_lt #inner>>::#method(&self.#member, #rhs_ref_value)
+    ));
+    let by_value = bounded(generics, inner, quote!(#trait_path<Output = #inner>));
+    let (impl_generics, _, where_clause) = by_value.split_for_impl();
+    let by_ref = bounded(
+        &with_lifetimes(generics, &[&lhs_lt, &rhs_lt]),
+        &parse_quote!(&#lhs_lt #inner),
+        quote!(#trait_path<&#rhs_lt #inner, Output = #inner>),
+    );
+    let (ref_impl_generics, _, ref_where_clause) = by_ref.split_for_impl();
+    quote! {
+        impl #impl_generics #trait_path<#rhs_ty> for #self_ty #where_clause {
+            type Output = #self_ty;
+
+            fn #method(self, rhs: #rhs_ty) -> #self_ty {
+                #construct
+            }
+        }
+        impl #ref_impl_generics #trait_path<#rhs_ref_ty> for &#lhs_lt #self_ty #ref_where_clause {
+            type Output = #self_ty;
+
+            fn #method(self, rhs: #rhs_ref_ty) -> #self_ty {
+                #construct_ref
+            }
+        }
+    }
+}
+
+/// Generates multiplication and division of the wrapper by a scalar, along with multiplying the
+/// scalar by the wrapper. When the scalar is the inner type, the impls `ops_inner` already
+/// provides are left out.
+fn expand_scalar(def: &Definition, scalar: &Type) -> TokenStream2 {
+    let Definition {
+        ident: name,
+        generics,
+        inner,
+        member,
+        ..
+    } = def;
+    let (_, type_generics, _) = generics.split_for_impl();
+    let self_ty = quote!(#name #type_generics);
+    let lhs_lt = Lifetime::new("'__lhs", Span::call_site());
+    let is_inner = quote!(#scalar).to_string() == quote!(#inner).to_string();
+    let provided = |op: &TokenStream2| {
+        is_inner
+            && def
+                .options
+                .ops_inner
+                .iter()
+                .any(|inner_op| *inner_op == op.to_string())
+    };
+
+    let ops = [
+        (quote!(Mul), quote!(mul), quote!(MulAssign), quote!(mul_assign)),
+        (quote!(Div), quote!(div), quote!(DivAssign), quote!(div_assign)),
+    ]
+    .iter()
+    .map(|(op, method, assign_op, assign_method)| {
+        let construct = def.construct(quote!(
+            <#inner as ::core::ops::#op<#scalar>>::#method(self.#member, rhs)
+        ));
+        let construct_ref = def.construct(quote!(
+            <&#lhs_lt #inner as ::core::ops::#op<#scalar>>::#method(&self.#member, rhs)
+        ));
+        let by_value = bounded(
+            generics,
+            inner,
+            quote!(::core::ops::#op<#scalar, Output = #inner>),
+        );
+        let (impl_generics, _, where_clause) = by_value.split_for_impl();
+        let by_ref = bounded(
+            &with_lifetimes(generics, &[&lhs_lt]),
+            &parse_quote!(&#lhs_lt #inner),
+            quote!(::core::ops::#op<#scalar, Output = #inner>),
+        );
+        let (ref_impl_generics, _, ref_where_clause) = by_ref.split_for_impl();
+        let assign = bounded(generics, inner, quote!(::core::ops::#assign_op<#scalar>));
+        let (assign_impl_generics, _, assign_where_clause) = assign.split_for_impl();
+        let by_value = if provided(op) {
+            TokenStream2::new()
+        } else {
+            quote! {
+                impl #impl_generics ::core::ops::#op<#scalar> for #self_ty #where_clause {
+                    type Output = #self_ty;
+
+                    fn #method(self, rhs: #scalar) -> #self_ty {
+                        #construct
+                    }
+                }
+            }
+        };
+        let assign = if provided(assign_op) {
+            TokenStream2::new()
+        } else {
+            quote! {
+                impl #assign_impl_generics ::core::ops::#assign_op<#scalar> for #self_ty #assign_where_clause {
+                    fn #assign_method(&mut self, rhs: #scalar) {
+                        <#inner as ::core::ops::#assign_op<#scalar>>::#assign_method(&mut self.#member, rhs)
+                    }
+                }
+            }
+        };
+        quote! {
+            #by_value
+            impl #ref_impl_generics ::core::ops::#op<#scalar> for &#lhs_lt #self_ty #ref_where_clause {
+                type Output = #self_ty;
+
+                fn #method(self, rhs: #scalar) -> #self_ty {
+                    #construct_ref
+                }
+            }
+            #assign
+        }
+    })
+    .collect::<Vec<_>>();
+
+    let commuted = def.construct(quote!(
+        <#scalar as ::core::ops::Mul<#inner>>::mul(self, rhs.#member)
+    ));
+    let commuted_generics = bounded(
+        generics,
+        scalar,
+        quote!(::core::ops::Mul<#inner, Output = #inner>),
+    );
+    let (impl_generics, _, where_clause) = commuted_generics.split_for_impl();
+    quote! {
+        #(#ops)*
+        impl #impl_generics ::core::ops::Mul<#self_ty> for #scalar #where_clause {
+            type Output = #self_ty;
+
+            fn mul(self, rhs: #self_ty) -> #self_ty {
+                #commuted
+            }
+        }
+    }
+}
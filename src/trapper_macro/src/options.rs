@@ -0,0 +1,723 @@
+use crate::ops;
+use quote::ToTokens;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::{
+    parenthesized, parse, parse_quote, token, Attribute, Ident, LitStr, Path, Token, Type,
+    Visibility,
+};
+
+/// The reference conversions a wrapper implements, each backed by a capability trait
+pub struct Capabilities {
+    pub wrap_ref: bool,
+    pub wrap_mut: bool,
+    pub unwrap_ref: bool,
+    pub unwrap_mut: bool,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Capabilities {
+            wrap_ref: true,
+            wrap_mut: true,
+            unwrap_ref: true,
+            unwrap_mut: true,
+        }
+    }
+}
+
+/// Options given to `newtype!` through `#[newtype(...)]` attributes, or as the arguments of
+/// `#[transparent(...)]`
+#[derive(Default)]
+pub struct Options {
+    pub capabilities: Capabilities,
+    /// The function checking values before they're wrapped, along with the error it returns
+    pub validate: Option<(Path, Type)>,
+    /// The visibility of construction and mutable unwrapping for sealed wrappers
+    pub wrap: Option<Visibility>,
+    /// Whether the inner type is unsized even though it can't be told from its syntax
+    pub unsized_inner: bool,
+    /// The path to the trapper crate used by the generated code
+    pub krate: Option<Path>,
+    /// The traits forwarded to the inner type, bounded on the inner type implementing them
+    pub derives: Vec<Ident>,
+    /// The operators forwarded between the wrapper and itself
+    pub ops: Vec<Ident>,
+    /// The operators forwarded between the wrapper and its inner type
+    pub ops_inner: Vec<Ident>,
+    /// The types the wrapper can be multiplied and divided by
+    pub scalars: Vec<Type>,
+    /// The formatting traits forwarded to the inner value as is
+    pub fmt: Vec<Ident>,
+    /// The format string `Display` is implemented with, with the inner value as its argument
+    pub display: Option<LitStr>,
+    /// The `std::io` traits forwarded to the inner value
+    pub io: Vec<Ident>,
+    /// Whether the wrapper borrows as its inner type, along with the comparison traits it forwards
+    /// to it being added to `derives`
+    pub borrow: bool,
+    /// Implements `From<Inner>` for the wrapper, or `TryFrom` for validated wrappers
+    pub from: Option<Ident>,
+    /// Implements `From` of the wrapper for the inner type
+    pub into: Option<Ident>,
+    /// Implements `From<&Inner>` for a reference to the wrapper, or `TryFrom` for validated
+    /// wrappers
+    pub from_ref: Option<Ident>,
+    /// The collection traits forwarded to the inner value
+    pub collection: Vec<Ident>,
+    /// Implements `Deref` to the inner type
+    pub deref: Option<Ident>,
+    /// Implements `DerefMut` to the inner type, along with `Deref`
+    pub deref_mut: Option<Ident>,
+}
+
+impl Options {
+    /// Removes every `#[newtype(...)]` attribute from the list and parses the options in them
+    pub fn extract(attrs: &mut Vec<Attribute>) -> parse::Result<Self> {
+        let mut list = Vec::new();
+        let mut result = Ok(());
+        attrs.retain(|attr| {
+            if !attr.path.is_ident("newtype") {
+                return true;
+            }
+            if result.is_ok() {
+                result = syn::parse2::<OptionList>(attr.tts.clone())
+                    .map(|options| list.extend(options.0));
+            }
+            false
+        });
+        result?;
+        Self::from_list(list)
+    }
+
+    /// Returns the path to the trapper crate, which is `trapper` unless given with `crate = path`
+    pub fn krate(&self) -> Path {
+        match &self.krate {
+            Some(path) => path.clone(),
+            None => parse_quote!(trapper),
+        }
+    }
+
+    /// Parses the `#![newtype(crate = path)]` attributes at the start of a `newtype!` invocation,
+    /// returning the crate path they set for every wrapper in it
+    pub fn default_crate(attrs: &[Attribute]) -> parse::Result<Option<Path>> {
+        let mut krate = None;
+        for attr in attrs {
+            if !attr.path.is_ident("newtype") {
+                return Err(parse::Error::new_spanned(
+                    &attr.path,
+                    "expected `#![newtype(crate = path)]`",
+                ));
+            }
+            for opt in syn::parse2::<OptionList>(attr.tts.clone())?.0 {
+                match opt {
+                    NewTypeOption::Crate(path) => set_once(&mut krate, path)?,
+                    _ => {
+                        return Err(parse::Error::new(
+                            opt.name().span(),
+                            "only `crate` can be set for every wrapper in an invocation",
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(krate.map(|(_, path)| path))
+    }
+
+    /// Checks that the visibility of the wrapped field doesn't undo the options
+    pub fn check_field(&self, vis: &Visibility) -> parse::Result<()> {
+        if self.validate.is_some() && !is_private(vis) {
+            return Err(parse::Error::new_spanned(
+                vis,
+                "the field of a validated wrapper must be private, since it could be used to construct the wrapper unchecked",
+            ));
+        }
+        if let Some(wrap) = &self.wrap {
+            // visibilities can't be compared in general, so only the one given to `wrap` itself
+            // is allowed besides private fields
+            let same = vis.into_token_stream().to_string() == wrap.into_token_stream().to_string();
+            if !is_private(vis) && !same {
+                return Err(parse::Error::new_spanned(
+                    vis,
+                    "the field of a sealed wrapper must be private or have the visibility given to `wrap`, since it could be used to construct the wrapper",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the given options against each other and collects them
+    fn from_list(list: Vec<NewTypeOption>) -> parse::Result<Self> {
+        let mut options = Options::default();
+        let mut validate = None;
+        let mut error = None;
+        let mut wrap = None;
+        let mut krate = None;
+        let mut arithmetic = None;
+        let mut display = None;
+        let mut mutating = None;
+        let mut borrow = None;
+        let binary = ops::BINARY.iter().map(|op| op.to_string());
+        let assign = ops::BINARY.iter().map(|op| format!("{}Assign", op));
+        let binary_ops = binary.chain(assign).collect::<Vec<_>>();
+        let binary_ops = binary_ops.iter().map(String::as_str).collect::<Vec<_>>();
+        let all_ops = binary_ops
+            .iter()
+            .chain(ops::UNARY)
+            .cloned()
+            .collect::<Vec<_>>();
+        for opt in list {
+            match opt {
+                NewTypeOption::Skip((_, capabilities)) => {
+                    for capability in capabilities {
+                        let enabled = match capability.to_string().as_str() {
+                            "WrapRef" => &mut options.capabilities.wrap_ref,
+                            "WrapMut" => &mut options.capabilities.wrap_mut,
+                            "UnwrapRef" => &mut options.capabilities.unwrap_ref,
+                            "UnwrapMut" => &mut options.capabilities.unwrap_mut,
+                            _ => return Err(parse::Error::new(
+                                capability.span(),
+                                "expected one of `WrapRef`, `WrapMut`, `UnwrapRef` or `UnwrapMut`",
+                            )),
+                        };
+                        *enabled = false;
+                    }
+                }
+                NewTypeOption::Validate(path) => set_once(&mut validate, path)?,
+                NewTypeOption::Error(ty) => set_once(&mut error, ty)?,
+                NewTypeOption::Wrap(vis) => set_once(&mut wrap, vis)?,
+                NewTypeOption::Crate(path) => set_once(&mut krate, path)?,
+                NewTypeOption::Ops((name, traits)) => {
+                    add_traits(&mut options.ops, traits, &all_ops)?;
+                    arithmetic.get_or_insert(name);
+                }
+                NewTypeOption::OpsInner((name, traits)) => {
+                    add_traits(&mut options.ops_inner, traits, &binary_ops)?;
+                    arithmetic.get_or_insert(name);
+                }
+                NewTypeOption::Scalar((name, scalars)) => {
+                    options.scalars.extend(scalars);
+                    arithmetic.get_or_insert(name);
+                }
+                NewTypeOption::Fmt((_, traits)) => add_traits(
+                    &mut options.fmt,
+                    traits,
+                    &[
+                        "Display", "Debug", "LowerHex", "UpperHex", "Octal", "Binary", "LowerExp",
+                        "UpperExp", "Write",
+                    ],
+                )?,
+                NewTypeOption::Io((name, traits)) => {
+                    add_traits(
+                        &mut options.io,
+                        traits,
+                        &["Read", "Write", "BufRead", "Seek"],
+                    )?;
+                    mutating.get_or_insert(name);
+                }
+                NewTypeOption::Collection((_, traits)) => add_traits(
+                    &mut options.collection,
+                    traits,
+                    &[
+                        "IntoIterator",
+                        "FromIterator",
+                        "Extend",
+                        "Index",
+                        "IndexMut",
+                        "Default",
+                        "Sum",
+                        "Product",
+                    ],
+                )?,
+                NewTypeOption::Display(format) => set_once(&mut display, format)?,
+                NewTypeOption::Borrow(borrowed) => set_once(&mut borrow, borrowed)?,
+                NewTypeOption::Unsized(name) => {
+                    if options.unsized_inner {
+                        return Err(parse::Error::new(
+                            name.span(),
+                            "`unsized` can only be given once",
+                        ));
+                    }
+                    options.unsized_inner = true;
+                }
+                NewTypeOption::From(name) => set_flag(&mut options.from, name)?,
+                NewTypeOption::Into(name) => set_flag(&mut options.into, name)?,
+                NewTypeOption::FromRef(name) => set_flag(&mut options.from_ref, name)?,
+                NewTypeOption::Deref(name) => set_flag(&mut options.deref, name)?,
+                NewTypeOption::DerefMut(name) => set_flag(&mut options.deref_mut, name)?,
+                NewTypeOption::Derive((_, traits)) => add_traits(
+                    &mut options.derives,
+                    traits,
+                    &[
+                        "Clone",
+                        "Copy",
+                        "PartialEq",
+                        "Eq",
+                        "PartialOrd",
+                        "Ord",
+                        "Hash",
+                        "Debug",
+                        "Default",
+                    ],
+                )?,
+            }
+        }
+
+        options.krate = krate.map(|(_, path)| path);
+
+        // `Default` is the same as its derive, which also checks it against the other options
+        if let Some(index) = options.collection.iter().position(|name| name == "Default") {
+            let default = options.collection.remove(index);
+            if !options.derives.contains(&default) {
+                options.derives.push(default);
+            }
+        }
+
+        if let Some((name, traits)) = borrow {
+            if !options.capabilities.unwrap_ref {
+                return Err(parse::Error::new(
+                    name.span(),
+                    "`borrow` can't be used when `UnwrapRef` is skipped",
+                ));
+            }
+            // the comparisons have to agree with the inner type for lookups by it to work, but
+            // they can be limited to the ones the inner type implements, like for `f64`
+            let comparisons = ["PartialEq", "Eq", "PartialOrd", "Ord", "Hash"];
+            let mut forwarded = Vec::new();
+            match traits {
+                Some(traits) => add_traits(&mut forwarded, traits, &comparisons)?,
+                None => forwarded.extend(
+                    comparisons
+                        .iter()
+                        .map(|comparison| Ident::new(comparison, name.span())),
+                ),
+            }
+            for derive in forwarded {
+                if !options.derives.contains(&derive) {
+                    options.derives.push(derive);
+                }
+            }
+            options.borrow = true;
+        }
+
+        if let Some(debug) = options.fmt.iter().find(|name| *name == "Debug") {
+            if options.derives.iter().any(|name| name == "Debug") {
+                return Err(parse::Error::new(
+                    debug.span(),
+                    "`Debug` can either be derived or forwarded transparently, but not both",
+                ));
+            }
+        }
+        if let Some((name, format)) = display {
+            if options.fmt.iter().any(|name| name == "Display") {
+                return Err(parse::Error::new(
+                    name.span(),
+                    "`display` can't be used when `Display` is forwarded with `fmt`",
+                ));
+            }
+            options.display = Some(format);
+        }
+
+        if let Some((name, vis)) = wrap {
+            if validate.is_some() {
+                return Err(parse::Error::new(
+                    name.span(),
+                    "`wrap` can't be used with `validate`, which already hides the unchecked conversions",
+                ));
+            }
+            options.wrap = Some(vis);
+        }
+
+        options.validate = match (validate, error) {
+            (Some((_, path)), Some((_, ty))) => {
+                // the unchecked conversions would let any value bypass the validator
+                options.capabilities.wrap_ref = false;
+                options.capabilities.wrap_mut = false;
+                options.capabilities.unwrap_mut = false;
+                Some((path, ty))
+            }
+            (None, None) => None,
+            (Some((name, _)), None) => {
+                return Err(parse::Error::new(
+                    name.span(),
+                    "`validate` requires the error type it returns, given with `error = Type`",
+                ))
+            }
+            (None, Some((name, _))) => {
+                return Err(parse::Error::new(
+                    name.span(),
+                    "`error` can only be used with `validate`",
+                ))
+            }
+        };
+
+        if let (Some(_), Some(name)) = (
+            &options.wrap,
+            options.from.as_ref().or(options.from_ref.as_ref()),
+        ) {
+            return Err(parse::Error::new(
+                name.span(),
+                format!(
+                    "`{}` can't be used for sealed wrappers, since it would make construction public",
+                    name
+                ),
+            ));
+        }
+        if let (Some(_), Some(name)) = (&options.validate, &options.from) {
+            return Err(parse::Error::new(
+                name.span(),
+                "`from` can't be used for validated wrappers, which always implement `TryFrom` of the inner type",
+            ));
+        }
+        if let (false, Some(name)) = (options.capabilities.wrap_ref, &options.from_ref) {
+            if options.validate.is_none() {
+                return Err(parse::Error::new(
+                    name.span(),
+                    "`from_ref` can't be used when `WrapRef` is skipped",
+                ));
+            }
+        }
+
+        if let (false, Some(name)) = (
+            options.capabilities.unwrap_ref,
+            options.deref.as_ref().or(options.deref_mut.as_ref()),
+        ) {
+            return Err(parse::Error::new(
+                name.span(),
+                format!("`{}` can't be used when `UnwrapRef` is skipped", name),
+            ));
+        }
+
+        if options.validate.is_some() || options.wrap.is_some() {
+            if let Some(name) = arithmetic {
+                return Err(parse::Error::new(
+                    name.span(),
+                    format!(
+                        "`{}` can't be used for validated or sealed wrappers, since the results of operators are wrapped unchecked",
+                        name
+                    ),
+                ));
+            }
+            let fmt_write = options.fmt.iter().find(|name| *name == "Write");
+            let deref_mut = options.deref_mut.as_ref();
+            if let Some(name) = mutating.as_ref().or(fmt_write).or(deref_mut) {
+                return Err(parse::Error::new(
+                    name.span(),
+                    format!(
+                        "`{}` can't be used for validated or sealed wrappers, since it mutates the inner value unchecked",
+                        name
+                    ),
+                ));
+            }
+            let constructing = options
+                .collection
+                .iter()
+                .find(|name| *name == "FromIterator" || *name == "Sum" || *name == "Product");
+            if let Some(name) = constructing {
+                return Err(parse::Error::new(
+                    name.span(),
+                    format!(
+                        "`{}` can't be forwarded for validated or sealed wrappers, since it would construct them unchecked",
+                        name
+                    ),
+                ));
+            }
+            let mutating_collection = options
+                .collection
+                .iter()
+                .find(|name| *name == "Extend" || *name == "IndexMut");
+            if let Some(name) = mutating_collection {
+                return Err(parse::Error::new(
+                    name.span(),
+                    format!(
+                        "`{}` can't be forwarded for validated or sealed wrappers, since it mutates the inner value unchecked",
+                        name
+                    ),
+                ));
+            }
+            if let Some(default) = options.derives.iter().find(|name| *name == "Default") {
+                return Err(parse::Error::new(
+                    default.span(),
+                    "`Default` can't be derived for validated or sealed wrappers, since it would construct them unchecked",
+                ));
+            }
+        }
+
+        if let (false, Some(name)) = (options.capabilities.unwrap_mut, &options.deref_mut) {
+            return Err(parse::Error::new(
+                name.span(),
+                "`deref_mut` can't be used when `UnwrapMut` is skipped",
+            ));
+        }
+
+        Ok(options)
+    }
+}
+
+/// Parses the comma separated options given as the arguments of an attribute macro
+impl parse::Parse for Options {
+    fn parse(input: parse::ParseStream) -> parse::Result<Self> {
+        let list = input.parse_terminated::<_, Token![,]>(NewTypeOption::parse)?;
+        Self::from_list(list.into_iter().collect())
+    }
+}
+
+/// Returns whether the visibility only allows access from the current module
+fn is_private(vis: &Visibility) -> bool {
+    match vis {
+        Visibility::Inherited => true,
+        Visibility::Restricted(restricted) => {
+            restricted.in_token.is_none() && restricted.path.is_ident("self")
+        }
+        _ => false,
+    }
+}
+
+/// Sets an option that can only be given once, keeping the name it was given with for errors
+fn set_once<T>(slot: &mut Option<(Ident, T)>, value: (Ident, T)) -> parse::Result<()> {
+    if slot.is_some() {
+        return Err(parse::Error::new(
+            value.0.span(),
+            format!("`{}` can only be given once", value.0),
+        ));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Sets an option given as a bare word, which can only be given once
+fn set_flag(slot: &mut Option<Ident>, name: Ident) -> parse::Result<()> {
+    if slot.is_some() {
+        return Err(parse::Error::new(
+            name.span(),
+            format!("`{}` can only be given once", name),
+        ));
+    }
+    *slot = Some(name);
+    Ok(())
+}
+
+/// Adds traits given to an option to a list, checking that each one is supported and only given
+/// once
+fn add_traits(
+    list: &mut Vec<Ident>,
+    traits: Punctuated<Ident, Token![,]>,
+    supported: &[&str],
+) -> parse::Result<()> {
+    for name in traits {
+        if !supported.iter().any(|supported| name == supported) {
+            let expected = supported
+                .iter()
+                .map(|supported| format!("`{}`", supported))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(parse::Error::new(
+                name.span(),
+                format!("expected one of {}", expected),
+            ));
+        }
+        if list.contains(&name) {
+            return Err(parse::Error::new(
+                name.span(),
+                format!("`{}` is already given", name),
+            ));
+        }
+        list.push(name);
+    }
+    Ok(())
+}
+
+/// A single option in a `#[newtype(...)]` attribute
+enum NewTypeOption {
+    /// `skip(WrapRef, ...)`, opting out of capability traits
+    Skip((Ident, Punctuated<Ident, Token![,]>)),
+    /// `validate = path::to::fn`, checking values before they're wrapped
+    Validate((Ident, Path)),
+    /// `error = Type`, the error returned by the `validate` function
+    Error((Ident, Type)),
+    /// `wrap = pub(crate)`, restricting construction and mutable unwrapping
+    Wrap((Ident, Visibility)),
+    /// `crate = path::to::trapper`, the path used to refer to trapper in the generated code
+    Crate((Ident, Path)),
+    /// `derive(Clone, ...)`, forwarding traits to the inner type
+    Derive((Ident, Punctuated<Ident, Token![,]>)),
+    /// `fmt(Display, ...)`, forwarding formatting traits to the inner value as is
+    Fmt((Ident, Punctuated<Ident, Token![,]>)),
+    /// `display = "id#{}"`, implementing `Display` with a format string
+    Display((Ident, LitStr)),
+    /// `borrow` or `borrow(PartialEq, ...)`, borrowing as the inner type and comparing and hashing
+    /// like it
+    Borrow((Ident, Option<Punctuated<Ident, Token![,]>>)),
+    /// `unsized`, treating the inner type as unsized
+    Unsized(Ident),
+    /// `from`, converting the inner value into the wrapper
+    From(Ident),
+    /// `into`, converting the wrapper into its inner value
+    Into(Ident),
+    /// `from_ref`, converting a reference to the inner value into a reference to the wrapper
+    FromRef(Ident),
+    /// `deref`, dereferencing to the inner value
+    Deref(Ident),
+    /// `deref_mut`, dereferencing mutably to the inner value
+    DerefMut(Ident),
+    /// `collection(IntoIterator, ...)`, forwarding collection traits to the inner value
+    Collection((Ident, Punctuated<Ident, Token![,]>)),
+    /// `io(Read, ...)`, forwarding `std::io` traits to the inner value
+    Io((Ident, Punctuated<Ident, Token![,]>)),
+    /// `ops(Add, ...)`, forwarding operators between the wrapper and itself
+    Ops((Ident, Punctuated<Ident, Token![,]>)),
+    /// `ops_inner(Add, ...)`, forwarding operators between the wrapper and its inner type
+    OpsInner((Ident, Punctuated<Ident, Token![,]>)),
+    /// `scalar(f64, ...)`, multiplying and dividing the wrapper by the given types
+    Scalar((Ident, Punctuated<Type, Token![,]>)),
+}
+
+impl NewTypeOption {
+    /// Returns the name the option was given with
+    fn name(&self) -> &Ident {
+        match self {
+            NewTypeOption::Skip((name, _))
+            | NewTypeOption::Derive((name, _))
+            | NewTypeOption::Fmt((name, _))
+            | NewTypeOption::Io((name, _))
+            | NewTypeOption::Collection((name, _))
+            | NewTypeOption::Ops((name, _))
+            | NewTypeOption::OpsInner((name, _)) => name,
+            NewTypeOption::Scalar((name, _)) => name,
+            NewTypeOption::Display((name, _)) => name,
+            NewTypeOption::Validate((name, _)) | NewTypeOption::Crate((name, _)) => name,
+            NewTypeOption::Error((name, _)) => name,
+            NewTypeOption::Wrap((name, _)) => name,
+            NewTypeOption::Borrow((name, _)) => name,
+            NewTypeOption::Unsized(name)
+            | NewTypeOption::From(name)
+            | NewTypeOption::Into(name)
+            | NewTypeOption::FromRef(name)
+            | NewTypeOption::Deref(name)
+            | NewTypeOption::DerefMut(name) => name,
+        }
+    }
+}
+
+impl parse::Parse for NewTypeOption {
+    fn parse(input: parse::ParseStream) -> parse::Result<Self> {
+        let name = input.call(Ident::parse_any)?;
+        match name.to_string().as_str() {
+            "skip" => {
+                let content;
+                parenthesized!(content in input);
+                Ok(NewTypeOption::Skip((
+                    name,
+                    content.parse_terminated(Ident::parse)?,
+                )))
+            }
+            "derive" => {
+                let content;
+                parenthesized!(content in input);
+                Ok(NewTypeOption::Derive((
+                    name,
+                    content.parse_terminated(Ident::parse)?,
+                )))
+            }
+            "fmt" => {
+                let content;
+                parenthesized!(content in input);
+                Ok(NewTypeOption::Fmt((
+                    name,
+                    content.parse_terminated(Ident::parse)?,
+                )))
+            }
+            "collection" => {
+                let content;
+                parenthesized!(content in input);
+                Ok(NewTypeOption::Collection((
+                    name,
+                    content.parse_terminated(Ident::parse)?,
+                )))
+            }
+            "io" => {
+                let content;
+                parenthesized!(content in input);
+                Ok(NewTypeOption::Io((
+                    name,
+                    content.parse_terminated(Ident::parse)?,
+                )))
+            }
+            "borrow" => {
+                let traits = if input.peek(token::Paren) {
+                    let content;
+                    parenthesized!(content in input);
+                    Some(content.parse_terminated(Ident::parse)?)
+                } else {
+                    None
+                };
+                Ok(NewTypeOption::Borrow((name, traits)))
+            }
+            "unsized" => Ok(NewTypeOption::Unsized(name)),
+            "from" => Ok(NewTypeOption::From(name)),
+            "into" => Ok(NewTypeOption::Into(name)),
+            "from_ref" => Ok(NewTypeOption::FromRef(name)),
+            "deref" => Ok(NewTypeOption::Deref(name)),
+            "deref_mut" => Ok(NewTypeOption::DerefMut(name)),
+            "display" => {
+                input.parse::<Token![=]>()?;
+                Ok(NewTypeOption::Display((name, input.parse()?)))
+            }
+            "ops" => {
+                let content;
+                parenthesized!(content in input);
+                Ok(NewTypeOption::Ops((
+                    name,
+                    content.parse_terminated(Ident::parse)?,
+                )))
+            }
+            "ops_inner" => {
+                let content;
+                parenthesized!(content in input);
+                Ok(NewTypeOption::OpsInner((
+                    name,
+                    content.parse_terminated(Ident::parse)?,
+                )))
+            }
+            "scalar" => {
+                let content;
+                parenthesized!(content in input);
+                Ok(NewTypeOption::Scalar((
+                    name,
+                    content.parse_terminated(Type::parse)?,
+                )))
+            }
+            "validate" => {
+                input.parse::<Token![=]>()?;
+                Ok(NewTypeOption::Validate((name, input.parse()?)))
+            }
+            "error" => {
+                input.parse::<Token![=]>()?;
+                Ok(NewTypeOption::Error((name, input.parse()?)))
+            }
+            "wrap" => {
+                input.parse::<Token![=]>()?;
+                Ok(NewTypeOption::Wrap((name, input.parse()?)))
+            }
+            "crate" => {
+                input.parse::<Token![=]>()?;
+                Ok(NewTypeOption::Crate((
+                    name,
+                    input.call(Path::parse_mod_style)?,
+                )))
+            }
+            _ => Err(parse::Error::new(name.span(), "unknown newtype option")),
+        }
+    }
+}
+
+/// The parenthesized, comma separated options of a `#[newtype(...)]` attribute
+struct OptionList(Punctuated<NewTypeOption, Token![,]>);
+
+impl parse::Parse for OptionList {
+    fn parse(input: parse::ParseStream) -> parse::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        Ok(OptionList(content.parse_terminated(NewTypeOption::parse)?))
+    }
+}